@@ -15,7 +15,7 @@ use rppal::spi::Bus;
 use tokio::time::timeout;
 use utilitaires_peripheriques::{
     capteur_luminosite::capteur::Veml7700, detecteur_mouvement::DetecteurMouvement, eclairage::Eclairage,
-    ecran::ecran::Wepd7In5BV2,
+    ecran::ecran::{MiseAJour, Wepd7In5BV2},
 };
 
 #[tokio::main]
@@ -132,8 +132,8 @@ pub async fn afficher_image(
     // Initialise cairo
     let mut surface = ImageSurface::create(
         Format::Rgb16_565,
-        Wepd7In5BV2::largeur() as i32,
-        Wepd7In5BV2::hauteur() as i32,
+        Wepd7In5BV2::LARGEUR as i32,
+        Wepd7In5BV2::HAUTEUR as i32,
     )
     .expect("Impossible d'initialiser la surface");
 
@@ -171,7 +171,9 @@ pub async fn afficher_image(
 
     if ecran.is_some() {
         log::info!("Afficher l'image");
-        ecran.as_mut().unwrap().mettre_a_jour().await?;
+        if ecran.as_mut().unwrap().mettre_a_jour().await? == MiseAJour::Inchangee {
+            log::info!("Image inchangée");
+        }
     }
     Ok(())
 }
@@ -193,8 +195,8 @@ fn afficher_jour(contexte: &Context) -> Result<(), Box<dyn std::error::Error>> {
     let texte_a_afficher: String = texte_a_afficher_characteres.into_iter().collect();
 
     let text_extent = contexte.text_extents(&texte_a_afficher)?;
-    let x_offset = (Wepd7In5BV2::largeur() as f64 - text_extent.width()) / 2.0;
-    let y_offset = (Wepd7In5BV2::hauteur() as f64 + text_extent.height()) / 4.;
+    let x_offset = (Wepd7In5BV2::LARGEUR as f64 - text_extent.width()) / 2.0;
+    let y_offset = (Wepd7In5BV2::HAUTEUR as f64 + text_extent.height()) / 4.;
     contexte.move_to(x_offset, y_offset);
     contexte.show_text(&texte_a_afficher)?;
 
@@ -204,8 +206,8 @@ fn afficher_jour(contexte: &Context) -> Result<(), Box<dyn std::error::Error>> {
         .format_localized("%e %B", Locale::fr_FR)
         .to_string();
     let text_extent = contexte.text_extents(&texte_a_afficher)?;
-    let x_offset = (Wepd7In5BV2::largeur() as f64 - text_extent.width()) / 2.0;
-    let y_offset = (Wepd7In5BV2::hauteur() as f64 + text_extent.height()) / 2.;
+    let x_offset = (Wepd7In5BV2::LARGEUR as f64 - text_extent.width()) / 2.0;
+    let y_offset = (Wepd7In5BV2::HAUTEUR as f64 + text_extent.height()) / 2.;
     contexte.move_to(x_offset, y_offset);
     contexte.show_text(texte_a_afficher)?;
 
@@ -216,8 +218,8 @@ fn afficher_jour(contexte: &Context) -> Result<(), Box<dyn std::error::Error>> {
         .to_string();
 
     let text_extent = contexte.text_extents(&texte_a_afficher)?;
-    let x_offset = (Wepd7In5BV2::largeur() as f64 - text_extent.width()) / 2.0;
-    let y_offset = (Wepd7In5BV2::hauteur() as f64 + text_extent.height() + 120. / 4.) * 3. / 4.;
+    let x_offset = (Wepd7In5BV2::LARGEUR as f64 - text_extent.width()) / 2.0;
+    let y_offset = (Wepd7In5BV2::HAUTEUR as f64 + text_extent.height() + 120. / 4.) * 3. / 4.;
     contexte.move_to(x_offset, y_offset);
     contexte.show_text(texte_a_afficher)?;
     Ok(())
@@ -235,8 +237,8 @@ fn afficher_valeurs_capteurs(
     let texte_a_afficher = format!("Luminosité: {luminosite_lux} lux");
 
     let text_extent = contexte.text_extents(&texte_a_afficher)?;
-    let x_offset = (Wepd7In5BV2::largeur() as f64 - text_extent.width()) / 2.0;
-    let y_offset = (Wepd7In5BV2::hauteur() as f64 + text_extent.height() + 120. / 4.) * 1. / 4.;
+    let x_offset = (Wepd7In5BV2::LARGEUR as f64 - text_extent.width()) / 2.0;
+    let y_offset = (Wepd7In5BV2::HAUTEUR as f64 + text_extent.height() + 120. / 4.) * 1. / 4.;
     contexte.move_to(x_offset, y_offset);
     contexte.show_text(&texte_a_afficher)?;
 
@@ -2,7 +2,6 @@
 
 use std::{
     env,
-    fs::{self},
     sync::{
         atomic::{AtomicBool, Ordering},
         Arc,
@@ -11,14 +10,25 @@ use std::{
 
 use chrono::{Local, Locale, Timelike};
 use utilitaires_peripheriques::capteur_luminosite::capteur::Veml7700;
-use utilitaires_peripheriques::{detecteur_mouvement::DetecteurMouvement, eclairage::Eclairage, ecran::ecran::Wepd7In5BV2};
-use image::ImageBuffer;
+use utilitaires_peripheriques::{
+    detecteur_mouvement::DetecteurMouvement,
+    eclairage::Eclairage,
+    ecran::{
+        ecran::{MiseAJour, Wepd7In5BV2},
+        erreur::ErreurEcran,
+        memoire_tampon::{MemoireTampon, TriCouleur},
+        texte::{Alignement, AlignementVertical, Polices, StyleTexte},
+        virtuel::EcranVirtuel,
+        zone::Zone,
+    },
+};
 use log::log_enabled;
 use log::Level::Info;
 use rppal::spi::Bus;
-use rusttype::{point, Font, PositionedGlyph, Scale};
 use tokio::time::timeout;
 
+const DOSSIER_POLICES: &str = "/usr/share/fonts/truetype/dejavu";
+
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     if env::var("RUST_LOG").is_err() {
@@ -119,11 +129,11 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     }
 
     log::info!("Arrêter");
-    if detecteur_mouvement.is_some() {
-        detecteur_mouvement.unwrap().arreter();
+    if let Some(mut detecteur_mouvement) = detecteur_mouvement {
+        detecteur_mouvement.arreter();
     }
-    if eclairage.is_some() {
-        eclairage.unwrap().arreter();
+    if let Some(mut eclairage) = eclairage {
+        eclairage.arreter();
     }
     Ok(())
 }
@@ -132,79 +142,77 @@ pub async fn afficher_image(
     ecran: &mut Option<Wepd7In5BV2>,
     luminosite_lux: String,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let donnees_rgb565 = match Local::now().minute() as f32
+    let polices = charger_polices()?;
+    let memoire_tampon = match Local::now().minute() as f32
         - ((Local::now().minute() as f32) / 10.).floor() * 10.
         < 1.
         || luminosite_lux.eq(&String::new())
     {
-        true => afficher_jour()?,
-        false => afficher_valeurs_capteurs(luminosite_lux)?,
+        true => afficher_jour(&polices),
+        false => afficher_valeurs_capteurs(&polices, luminosite_lux),
     };
 
-    afficher_image_rusttype(ecran, donnees_rgb565).await?;
+    afficher_image_rusttype(ecran, memoire_tampon).await?;
     Ok(())
 }
 
-fn afficher_jour() -> Result<Vec<u16>, Box<dyn std::error::Error>> {
-    log::info!("Afficher le jour courant");
-    let couleur = (255, 0, 0);
-    let fichier_police = &fs::read("/usr/share/fonts/truetype/dejavu/DejaVuSerif.ttf").unwrap();
-    let police = Font::try_from_bytes(fichier_police).unwrap();
-    let taille_police = Scale::uniform(140.);
+/// Police principale et police de repli, la variable d'environnement POLICE permet de choisir
+/// une autre police principale
+fn charger_polices() -> Result<Polices, ErreurEcran> {
+    let police = env::var("POLICE").unwrap_or(format!("{DOSSIER_POLICES}/DejaVuSerif.ttf"));
+    Polices::depuis_fichier(police)?.avec_fichier_repli(format!("{DOSSIER_POLICES}/DejaVuSans.ttf"))
+}
 
-    let mut donnees_rgb565: Vec<u16> =
-        vec![65535; Wepd7In5BV2::largeur() as usize * Wepd7In5BV2::hauteur() as usize];
+fn afficher_jour(polices: &Polices) -> MemoireTampon {
+    log::info!("Afficher le jour courant");
+    let style = StyleTexte::new(140.)
+        .avec_alignement(Alignement::Centre)
+        .avec_alignement_vertical(AlignementVertical::Milieu);
+    let mut memoire_tampon = MemoireTampon::new(Wepd7In5BV2::LARGEUR, Wepd7In5BV2::HAUTEUR);
 
     // Jour
     let texte_a_afficher = &Local::now()
         .format_localized("%A", Locale::fr_FR)
         .to_string();
-    let mut texte_a_afficher_characteres: Vec<char> = texte_a_afficher.chars().collect();
-    texte_a_afficher_characteres[0] = texte_a_afficher_characteres[0]
-        .to_uppercase()
-        .nth(0)
-        .unwrap();
-    let texte_a_afficher: String = texte_a_afficher_characteres.into_iter().collect();
-
-    let (glyphes, hauteur, largeur, _y_min) =
-        creer_glyphe_texte(&police, taille_police, &texte_a_afficher);
-
-    dessiner_glpyhe(
-        glyphes,
-        couleur,
-        Wepd7In5BV2::hauteur() as i32 / 5 - hauteur as i32 / 2,
-        Wepd7In5BV2::largeur() as i32 / 2 - largeur as i32 / 2,
-        &mut donnees_rgb565,
+    let mut texte_a_afficher_characteres = texte_a_afficher.chars();
+    let texte_a_afficher: String = texte_a_afficher_characteres
+        .next()
+        .map(|premier| premier.to_uppercase().chain(texte_a_afficher_characteres).collect())
+        .unwrap_or_default();
+    polices.dessiner(
+        &mut memoire_tampon,
+        zone_ligne(Wepd7In5BV2::HAUTEUR / 5),
+        &texte_a_afficher,
+        &style.avec_couleur(TriCouleur::Rouge),
     );
 
-    let couleur = (0, 0, 0);
     let texte_a_afficher = Local::now()
         .format_localized("%e %B", Locale::fr_FR)
         .to_string();
-    let (glyphes, hauteur, largeur, _y_min) =
-        creer_glyphe_texte(&police, taille_police, &texte_a_afficher);
-    dessiner_glpyhe(
-        glyphes,
-        couleur,
-        Wepd7In5BV2::hauteur() as i32 / 2 - hauteur as i32 / 2,
-        Wepd7In5BV2::largeur() as i32 / 2 - largeur as i32 / 2,
-        &mut donnees_rgb565,
+    polices.dessiner(
+        &mut memoire_tampon,
+        zone_ligne(Wepd7In5BV2::HAUTEUR / 2),
+        texte_a_afficher.trim(),
+        &style,
     );
 
     let texte_a_afficher = Local::now()
         .format_localized("%R", Locale::fr_FR)
         .to_string();
-    let (glyphes, hauteur, largeur, _y_min) =
-        creer_glyphe_texte(&police, taille_police, &texte_a_afficher);
-    dessiner_glpyhe(
-        glyphes,
-        couleur,
-        Wepd7In5BV2::hauteur() as i32 * 4 / 5 - hauteur as i32 / 2,
-        Wepd7In5BV2::largeur() as i32 / 2 - largeur as i32 / 2,
-        &mut donnees_rgb565,
+    polices.dessiner(
+        &mut memoire_tampon,
+        zone_ligne(Wepd7In5BV2::HAUTEUR * 4 / 5),
+        &texte_a_afficher,
+        &style,
     );
 
-    Ok(donnees_rgb565)
+    memoire_tampon
+}
+
+/// Zone de la largeur de l'écran centrée verticalement sur une ordonnée
+fn zone_ligne(y: usize) -> Zone {
+    let hauteur = Wepd7In5BV2::HAUTEUR / 3;
+    Zone::new(0, y.saturating_sub(hauteur / 2), Wepd7In5BV2::LARGEUR, hauteur)
 }
 
 async fn lire_luminosite(capteur_luminosite: &mut Option<Veml7700>) -> Option<f64> {
@@ -270,181 +278,49 @@ async fn lire_luminosite(capteur_luminosite: &mut Option<Veml7700>) -> Option<f6
     luminosite_lux
 }
 
-fn afficher_valeurs_capteurs(
-    luminosite_lux: String,
-) -> Result<Vec<u16>, Box<dyn std::error::Error>> {
+fn afficher_valeurs_capteurs(polices: &Polices, luminosite_lux: String) -> MemoireTampon {
     log::info!("Afficher la luminosité");
-    let couleur = (0, 0, 0);
-    let fichier_police = &fs::read("/usr/share/fonts/truetype/dejavu/DejaVuSerif.ttf").unwrap();
-    let police = Font::try_from_bytes(fichier_police).unwrap();
-    let taille_police = Scale::uniform(60.);
-
-    let mut donnees_rgb565: Vec<u16> =
-        vec![65535; Wepd7In5BV2::largeur() as usize * Wepd7In5BV2::hauteur() as usize];
+    let mut memoire_tampon = MemoireTampon::new(Wepd7In5BV2::LARGEUR, Wepd7In5BV2::HAUTEUR);
 
     let texte_a_afficher = format!("Luminosité: {luminosite_lux} lux");
-
-    let (glyphes, hauteur, largeur, _y_min) =
-        creer_glyphe_texte(&police, taille_police, &texte_a_afficher);
-    dessiner_glpyhe(
-        glyphes,
-        couleur,
-        Wepd7In5BV2::hauteur() as i32 / 2 - hauteur as i32 / 2,
-        Wepd7In5BV2::largeur() as i32 / 2 - largeur as i32 / 2,
-        &mut donnees_rgb565,
+    polices.dessiner(
+        &mut memoire_tampon,
+        Zone::new(0, 0, Wepd7In5BV2::LARGEUR, Wepd7In5BV2::HAUTEUR),
+        &texte_a_afficher,
+        &StyleTexte::new(60.)
+            .avec_alignement(Alignement::Centre)
+            .avec_alignement_vertical(AlignementVertical::Milieu),
     );
 
-    Ok(donnees_rgb565)
-}
-
-fn creer_glyphe_texte<'a>(
-    police: &'a Font,
-    taille_police: Scale,
-    texte: &str,
-) -> (Vec<PositionedGlyph<'a>>, u32, u32, u32) {
-    let v_metriques = police.v_metrics(taille_police);
-    let mut y_min = i32::MAX;
-    let mut y_max = i32::MIN;
-
-    let glyphes: Vec<PositionedGlyph> = police
-        .layout(&texte, taille_police, point(0., 0. + v_metriques.ascent))
-        .collect();
-
-    for glyphe in glyphes.iter() {
-        match glyphe.pixel_bounding_box() {
-            Some(pixel_bounding_box) => {
-                if y_min > pixel_bounding_box.min.y {
-                    y_min = pixel_bounding_box.min.y;
-                }
-                if y_max < pixel_bounding_box.max.y {
-                    y_max = pixel_bounding_box.max.y;
-                }
-            }
-            None => {}
-        }
-    }
-
-    // let hauteur = (v_metriques.ascent - v_metriques.descent).ceil() as u32;
-    let (hauteur, largeur) = match texte == "" {
-        true => (0, 0),
-        false => {
-            let largeur = {
-                let min_x = glyphes
-                    .first()
-                    .map(|g| g.pixel_bounding_box().unwrap().min.x)
-                    .unwrap();
-                let max_x = glyphes
-                    .last()
-                    .map(|g| g.pixel_bounding_box().unwrap().max.x)
-                    .unwrap();
-                (max_x - min_x) as u32
-            };
-            ((y_max - y_min) as u32, largeur)
-        }
-    };
-
-    (glyphes, hauteur, largeur, y_min as u32)
-}
-
-fn dessiner_glpyhe(
-    glyphes: Vec<PositionedGlyph>,
-    couleur: (u8, u8, u8),
-    hauteur: i32,
-    largeur: i32,
-    donnees_rgb565: &mut [u16],
-) {
-    let couleur_pixel_565 = convertir_rgb_888_en_reg_565(couleur);
-
-    for glyphe in glyphes {
-        if let Some(bounding_box) = glyphe.pixel_bounding_box() {
-            glyphe.draw(|x, y, v| {
-                let pixel;
-                if v < 0.5 {
-                    pixel = 65535;
-                } else {
-                    pixel = couleur_pixel_565
-                }
-                let y_pixel = y as i32 + bounding_box.min.y + hauteur as i32;
-                let x_pixel = x as i32 + bounding_box.min.x + largeur as i32;
-
-                if !(y_pixel < 0
-                    || x_pixel < 0
-                    || y_pixel >= Wepd7In5BV2::hauteur() as i32
-                    || x_pixel >= Wepd7In5BV2::largeur() as i32)
-                {
-                    donnees_rgb565[y_pixel as usize * Wepd7In5BV2::largeur() + x_pixel as usize] =
-                        pixel;
-                }
-            });
-        }
-    }
+    memoire_tampon
 }
 
 pub async fn afficher_image_rusttype(
     ecran: &mut Option<Wepd7In5BV2>,
-    donnees_rgb565: Vec<u16>,
+    memoire_tampon: MemoireTampon,
 ) -> Result<(), Box<dyn std::error::Error>> {
     // Uniquement si les journaux d'informations ou plus détaillés sont activés
     if log_enabled!(Info) {
-        let image = ImageBuffer::from_fn(
-            Wepd7In5BV2::largeur() as u32,
-            Wepd7In5BV2::hauteur() as u32,
-            |x, y| {
-                let pixel = donnees_rgb565[(y * Wepd7In5BV2::largeur() as u32 + x) as usize];
-
-                let bleu = ((pixel & 0x001F) << 3) as u8;
-                let vert = ((pixel & 0x07E0) >> 3) as u8;
-                let rouge = ((pixel & 0xF800) >> 8) as u8;
-
-                image::Rgb::<u8>([rouge, vert, bleu])
-            },
-        );
-
-        let repertoire_temporaire = env::temp_dir();
-        image
-            .save(repertoire_temporaire.join("ecran_afficher_temperature.png"))
-            .unwrap();
+        let mut ecran_virtuel: EcranVirtuel =
+            EcranVirtuel::new(env::temp_dir().join("ecran_afficher_temperature.png"))?;
+        ecran_virtuel.charger_memoire_tampon(memoire_tampon.clone())?;
+        ecran_virtuel.mettre_a_jour().await?;
     }
 
-    let donnees = convertir_vec_u16_vers_vec_u8(&donnees_rgb565);
-
     if ecran.is_some() {
         log::info!("Initialiser");
         ecran.as_mut().unwrap().initialiser().await?;
-        ecran.as_mut().unwrap().effacer_memoire_tampon()?;
         ecran
             .as_mut()
             .unwrap()
-            .sauvegarder_image_memoire_tampon(&donnees)?;
+            .charger_memoire_tampon(memoire_tampon)?;
     }
 
     if ecran.is_some() {
         log::info!("Afficher l'image");
-        ecran.as_mut().unwrap().mettre_a_jour().await?;
+        if ecran.as_mut().unwrap().mettre_a_jour().await? == MiseAJour::Inchangee {
+            log::info!("Image inchangée");
+        }
     }
     Ok(())
 }
-
-fn convertir_rgb_888_en_reg_565(couleur: (u8, u8, u8)) -> u16 {
-    let rgb_565 = (((couleur.0 & 0b11111000) as u16) << 8)
-        + ((couleur.1 & 0b11111100) << 3) as u16
-        + (couleur.2 >> 3) as u16;
-    rgb_565
-}
-
-fn convertir_vec_u16_vers_vec_u8(input: &[u16]) -> Vec<u8> {
-    let big_endian = cfg!(target_endian = "big");
-    let mut bytes = vec![0; 2 * input.len()];
-    let mut cpt = 0;
-    for value in input {
-        let pixel = match big_endian {
-            true => value.to_be_bytes(),
-            false => value.to_le_bytes(),
-        };
-        bytes[cpt] = pixel[0];
-        bytes[cpt + 1] = pixel[1];
-        cpt = cpt + 2;
-    }
-
-    bytes
-}
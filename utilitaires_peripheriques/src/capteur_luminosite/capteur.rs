@@ -99,7 +99,7 @@ impl Veml7700 {
             | (self.temps_integration.adresse() as u16) << 6
             | (self.persistance.adresse() as u16) << 4
             | (self.interruption_active as u16) << 1
-            | (self.mode_economie_energie.adresse() as u16) << 0;
+            | (self.mode_economie_energie.adresse() as u16);
 
         let configuration = match self.big_endian {
             true => configuration.to_be_bytes(),
@@ -224,9 +224,9 @@ impl Veml7700 {
         let gain_max: f64 = Gain::AlsGain2.valeur();
         let integration_time_max = TempsIntegration::AlsIt800MS.valeur();
 
-        return resolution_at_max
+        resolution_at_max
             * (integration_time_max / self.temps_integration.valeur())
-            * (gain_max / self.gain.valeur());
+            * (gain_max / self.gain.valeur())
     }
 
     pub fn activer_correction_non_lineaire_resolution(&mut self, active: bool) {
@@ -0,0 +1,12 @@
+/// Etat d'alimentation du contrôleur de l'écran suivi par le pilote
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum EtatAlimentation {
+    /// Contrôleur non initialisé ou dans un état inconnu, par exemple après une erreur
+    #[default]
+    Eteint,
+    /// Contrôleur hors tension et en veille profonde. Une réinitialisation matérielle est
+    /// nécessaire avant de lui envoyer des commandes.
+    Veille,
+    /// Contrôleur initialisé et sous tension, prêt à recevoir une image
+    Actif,
+}
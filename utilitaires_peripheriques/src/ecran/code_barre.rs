@@ -0,0 +1,353 @@
+use barcoders::sym::code128;
+use qrcode::{Color, EcLevel, QrCode};
+
+use crate::ecran::{
+    erreur::ErreurEcran,
+    memoire_tampon::{MemoireTampon, TriCouleur},
+    widget::{remplir, Contexte, Widget},
+    zone::Zone,
+};
+
+/// Largeur de la zone de silence d'un code QR, en modules
+const ZONE_SILENCE_QR: usize = 4;
+/// Largeur de la zone de silence d'un code-barres Code128, en modules
+const ZONE_SILENCE_CODE128: usize = 10;
+
+/// Niveau de correction d'erreur d'un code QR
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Correction {
+    /// Environ 7 % des modules peuvent être restaurés
+    Faible,
+    /// Environ 15 % des modules peuvent être restaurés
+    #[default]
+    Moyenne,
+    /// Environ 25 % des modules peuvent être restaurés
+    Quartile,
+    /// Environ 30 % des modules peuvent être restaurés
+    Haute,
+}
+
+impl From<Correction> for EcLevel {
+    fn from(correction: Correction) -> Self {
+        match correction {
+            Correction::Faible => EcLevel::L,
+            Correction::Moyenne => EcLevel::M,
+            Correction::Quartile => EcLevel::Q,
+            Correction::Haute => EcLevel::H,
+        }
+    }
+}
+
+/// Code QR dessiné avec des modules carrés d'un nombre entier de pixels
+pub struct CodeQr {
+    cote: usize,
+    modules: Vec<bool>,
+    zone_silence: usize,
+    couleur: TriCouleur,
+}
+
+impl CodeQr {
+    /// Encoder le texte avec le niveau de correction d'erreur choisi
+    pub fn new(texte: &str, correction: Correction) -> Result<Self, ErreurEcran> {
+        let code = QrCode::with_error_correction_level(texte, correction.into())
+            .map_err(|erreur| ErreurEcran::CodeBarreInvalide(erreur.to_string()))?;
+        Ok(Self {
+            cote: code.width(),
+            modules: code
+                .to_colors()
+                .into_iter()
+                .map(|couleur| couleur == Color::Dark)
+                .collect(),
+            zone_silence: ZONE_SILENCE_QR,
+            couleur: TriCouleur::Noir,
+        })
+    }
+
+    /// Choisir la largeur de la zone de silence, en modules
+    pub fn avec_zone_silence(mut self, zone_silence: usize) -> Self {
+        self.zone_silence = zone_silence;
+        self
+    }
+
+    /// Choisir la couleur des modules sombres
+    pub fn avec_couleur(mut self, couleur: TriCouleur) -> Self {
+        self.couleur = couleur;
+        self
+    }
+
+    /// Nombre de modules sur un côté du code, sans la zone de silence
+    pub fn cote(&self) -> usize {
+        self.cote
+    }
+
+    fn modules_totaux(&self) -> usize {
+        self.cote + 2 * self.zone_silence
+    }
+
+    fn echelle(&self, largeur: usize, hauteur: usize) -> usize {
+        largeur.min(hauteur) / self.modules_totaux()
+    }
+}
+
+impl Widget for CodeQr {
+    fn mesurer(&self, _contexte: &Contexte, largeur: usize, hauteur: usize) -> (usize, usize) {
+        let cote = self.modules_totaux() * self.echelle(largeur, hauteur);
+        (cote, cote)
+    }
+
+    fn dessiner(&self, _contexte: &Contexte, memoire_tampon: &mut MemoireTampon, zone: Zone) {
+        let echelle = self.echelle(zone.largeur, zone.hauteur);
+        if echelle == 0 {
+            log::warn!(
+                "Zone trop petite pour le code QR : {}×{} pixels pour {} modules",
+                zone.largeur,
+                zone.hauteur,
+                self.modules_totaux()
+            );
+            return;
+        }
+        let cote = self.modules_totaux() * echelle;
+        let x0 = zone.x + (zone.largeur - cote) / 2;
+        let y0 = zone.y + (zone.hauteur - cote) / 2;
+        remplir(
+            memoire_tampon,
+            Zone::new(x0, y0, cote, cote),
+            TriCouleur::Blanc,
+        );
+
+        let marge = self.zone_silence * echelle;
+        for (indice, _) in self
+            .modules
+            .iter()
+            .enumerate()
+            .filter(|(_, &sombre)| sombre)
+        {
+            let x = x0 + marge + (indice % self.cote) * echelle;
+            let y = y0 + marge + (indice / self.cote) * echelle;
+            remplir(
+                memoire_tampon,
+                Zone::new(x, y, echelle, echelle),
+                self.couleur,
+            );
+        }
+    }
+}
+
+/// Code-barres Code128 dont les barres occupent toute la hauteur de la zone
+pub struct Code128 {
+    modules: Vec<bool>,
+    zone_silence: usize,
+    couleur: TriCouleur,
+}
+
+impl Code128 {
+    /// Encoder le texte ASCII, avec le jeu C pour les nombres d'un nombre pair de chiffres
+    /// et le jeu B sinon
+    pub fn new(texte: &str) -> Result<Self, ErreurEcran> {
+        let numerique = !texte.is_empty()
+            && texte.len() % 2 == 0
+            && texte.bytes().all(|octet| octet.is_ascii_digit());
+        let jeu = if numerique { 'Ć' } else { 'Ɓ' };
+        let code = code128::Code128::new(format!("{jeu}{texte}"))
+            .map_err(|erreur| ErreurEcran::CodeBarreInvalide(erreur.to_string()))?;
+        Ok(Self {
+            modules: code
+                .encode()
+                .into_iter()
+                .map(|module| module == 1)
+                .collect(),
+            zone_silence: ZONE_SILENCE_CODE128,
+            couleur: TriCouleur::Noir,
+        })
+    }
+
+    /// Choisir la largeur des zones de silence à gauche et à droite, en modules
+    pub fn avec_zone_silence(mut self, zone_silence: usize) -> Self {
+        self.zone_silence = zone_silence;
+        self
+    }
+
+    /// Choisir la couleur des barres
+    pub fn avec_couleur(mut self, couleur: TriCouleur) -> Self {
+        self.couleur = couleur;
+        self
+    }
+
+    /// Nombre de modules du code-barres, sans les zones de silence
+    pub fn longueur(&self) -> usize {
+        self.modules.len()
+    }
+
+    fn modules_totaux(&self) -> usize {
+        self.modules.len() + 2 * self.zone_silence
+    }
+}
+
+impl Widget for Code128 {
+    fn mesurer(&self, _contexte: &Contexte, largeur: usize, hauteur: usize) -> (usize, usize) {
+        let modules = self.modules_totaux();
+        (modules * (largeur / modules), hauteur)
+    }
+
+    fn dessiner(&self, _contexte: &Contexte, memoire_tampon: &mut MemoireTampon, zone: Zone) {
+        let echelle = zone.largeur / self.modules_totaux();
+        if echelle == 0 || zone.hauteur == 0 {
+            log::warn!(
+                "Zone trop petite pour le code-barres : {}×{} pixels pour {} modules",
+                zone.largeur,
+                zone.hauteur,
+                self.modules_totaux()
+            );
+            return;
+        }
+        let largeur = self.modules_totaux() * echelle;
+        let x0 = zone.x + (zone.largeur - largeur) / 2;
+        remplir(
+            memoire_tampon,
+            Zone::new(x0, zone.y, largeur, zone.hauteur),
+            TriCouleur::Blanc,
+        );
+
+        let x0 = x0 + self.zone_silence * echelle;
+        for (indice, _) in self.modules.iter().enumerate().filter(|(_, &barre)| barre) {
+            remplir(
+                memoire_tampon,
+                Zone::new(x0 + indice * echelle, zone.y, echelle, zone.hauteur),
+                self.couleur,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDateTime;
+
+    use super::*;
+    use crate::ecran::texte::Polices;
+
+    fn polices() -> Polices {
+        Polices::depuis_fichier(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/polices/DejaVuSansMono.ttf"
+        ))
+        .unwrap()
+    }
+
+    /// Mémoire tampon rouge, pour distinguer les pixels non dessinés
+    fn memoire_tampon_rouge() -> MemoireTampon {
+        let mut memoire_tampon = MemoireTampon::new(240, 70);
+        memoire_tampon.effacer(TriCouleur::Rouge);
+        memoire_tampon
+    }
+
+    #[test]
+    fn code_qr_dans_une_petite_zone() {
+        let polices = polices();
+        let contexte = Contexte::new(&polices, NaiveDateTime::default());
+        let code = CodeQr::new("A", Correction::Faible).unwrap();
+        assert_eq!(code.cote(), 21);
+
+        // 29 modules avec la zone de silence : modules de 2 pixels, code de 58 pixels centré
+        let mut memoire_tampon = memoire_tampon_rouge();
+        let zone = Zone::new(8, 3, 64, 64);
+        assert_eq!(code.mesurer(&contexte, 64, 64), (58, 58));
+        code.dessiner(&contexte, &mut memoire_tampon, zone);
+        let (x0, y0) = (11, 6);
+        assert_eq!(memoire_tampon.pixel(x0 - 1, y0), Some(TriCouleur::Rouge));
+        assert_eq!(
+            memoire_tampon.pixel(x0 + 57, y0 + 57),
+            Some(TriCouleur::Blanc)
+        );
+        assert_eq!(
+            memoire_tampon.pixel(x0 + 58, y0 + 57),
+            Some(TriCouleur::Rouge)
+        );
+        // Zone de silence de 4 modules, puis motifs de repérage dans les coins
+        for i in 0..8 {
+            assert_eq!(
+                memoire_tampon.pixel(x0 + i, y0 + 8),
+                Some(TriCouleur::Blanc)
+            );
+            assert_eq!(
+                memoire_tampon.pixel(x0 + 8, y0 + i),
+                Some(TriCouleur::Blanc)
+            );
+        }
+        for (x, y) in [(8, 8), (9, 9), (49, 8), (8, 49)] {
+            assert_eq!(memoire_tampon.pixel(x0 + x, y0 + y), Some(TriCouleur::Noir));
+        }
+        assert_eq!(
+            memoire_tampon.pixel(x0 + 50, y0 + 8),
+            Some(TriCouleur::Blanc)
+        );
+    }
+
+    #[test]
+    fn code_qr_trop_grand() {
+        let polices = polices();
+        let contexte = Contexte::new(&polices, NaiveDateTime::default());
+        let code = CodeQr::new("A", Correction::Faible).unwrap();
+
+        // Moins d'un pixel par module : rien n'est dessiné
+        let mut memoire_tampon = memoire_tampon_rouge();
+        assert_eq!(code.mesurer(&contexte, 28, 64), (0, 0));
+        code.dessiner(&contexte, &mut memoire_tampon, Zone::new(0, 0, 28, 64));
+        assert_eq!(memoire_tampon, memoire_tampon_rouge());
+
+        let texte = "x".repeat(3000);
+        assert!(matches!(
+            CodeQr::new(&texte, Correction::Haute),
+            Err(ErreurEcran::CodeBarreInvalide(_))
+        ));
+    }
+
+    #[test]
+    fn code128_dans_une_petite_zone() {
+        let polices = polices();
+        let contexte = Contexte::new(&polices, NaiveDateTime::default());
+        let code = Code128::new("123456").unwrap();
+        // Départ, 3 paires de chiffres du jeu C et clé de contrôle de 11 modules, arrêt de 13
+        assert_eq!(code.longueur(), 68);
+
+        // 88 modules avec les zones de silence : modules de 2 pixels, code de 176 pixels centré
+        let mut memoire_tampon = memoire_tampon_rouge();
+        assert_eq!(code.mesurer(&contexte, 200, 20), (176, 20));
+        code.dessiner(&contexte, &mut memoire_tampon, Zone::new(10, 5, 200, 20));
+        let x0 = 22;
+        assert_eq!(memoire_tampon.pixel(x0 - 1, 5), Some(TriCouleur::Rouge));
+        assert_eq!(memoire_tampon.pixel(x0, 4), Some(TriCouleur::Rouge));
+        for x in x0..x0 + 20 {
+            assert_eq!(memoire_tampon.pixel(x, 5), Some(TriCouleur::Blanc));
+        }
+        // Le code commence et se termine par une barre
+        assert_eq!(memoire_tampon.pixel(x0 + 20, 5), Some(TriCouleur::Noir));
+        assert_eq!(memoire_tampon.pixel(x0 + 21, 24), Some(TriCouleur::Noir));
+        assert_eq!(memoire_tampon.pixel(x0 + 155, 5), Some(TriCouleur::Noir));
+        assert_eq!(memoire_tampon.pixel(x0 + 156, 5), Some(TriCouleur::Blanc));
+        assert_eq!(memoire_tampon.pixel(x0 + 176, 5), Some(TriCouleur::Rouge));
+    }
+
+    #[test]
+    fn code128_trop_grand() {
+        let polices = polices();
+        let contexte = Contexte::new(&polices, NaiveDateTime::default());
+        let code = Code128::new("123456").unwrap();
+
+        let mut memoire_tampon = memoire_tampon_rouge();
+        assert_eq!(code.mesurer(&contexte, 87, 20), (0, 20));
+        code.dessiner(&contexte, &mut memoire_tampon, Zone::new(0, 0, 87, 20));
+        assert_eq!(memoire_tampon, memoire_tampon_rouge());
+    }
+
+    #[test]
+    fn code128_caracteres_non_encodables() {
+        for texte in ["été", "100 €", "a\u{7f}"] {
+            assert!(
+                matches!(Code128::new(texte), Err(ErreurEcran::CodeBarreInvalide(_))),
+                "{texte}"
+            );
+        }
+        assert!(Code128::new("Abc-123").is_ok());
+    }
+}
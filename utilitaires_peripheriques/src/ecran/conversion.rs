@@ -0,0 +1,665 @@
+#[cfg(feature = "image")]
+use std::path::Path;
+
+#[cfg(feature = "image")]
+use image::{imageops::FilterType, DynamicImage, ImageReader};
+
+use crate::ecran::{
+    erreur::ErreurEcran,
+    memoire_tampon::{Gris4, MemoireTampon, TriCouleur},
+};
+
+/// Matrice de Bayer 4×4 utilisée pour le tramage ordonné
+pub(crate) const MATRICE_BAYER: [[f32; 4]; 4] = [
+    [0., 8., 2., 10.],
+    [12., 4., 14., 6.],
+    [3., 11., 1., 9.],
+    [15., 7., 13., 5.],
+];
+
+/// Diffusion de l'erreur de Floyd-Steinberg : (décalage x, décalage y, poids)
+const DIFFUSION_FLOYD_STEINBERG: [(isize, usize, f32); 4] = [
+    (1, 0, 7. / 16.),
+    (-1, 1, 3. / 16.),
+    (0, 1, 5. / 16.),
+    (1, 1, 1. / 16.),
+];
+
+/// Diffusion de l'erreur d'Atkinson (les 2/8 restants sont perdus)
+const DIFFUSION_ATKINSON: [(isize, usize, f32); 6] = [
+    (1, 0, 1. / 8.),
+    (2, 0, 1. / 8.),
+    (-1, 1, 1. / 8.),
+    (0, 1, 1. / 8.),
+    (1, 1, 1. / 8.),
+    (0, 2, 1. / 8.),
+];
+
+/// Algorithme de tramage
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Tramage {
+    /// Couleur la plus proche de la palette, sans tramage
+    Seuil,
+    /// Tramage ordonné avec une matrice de Bayer 4×4
+    Bayer,
+    /// Diffusion de l'erreur de Floyd-Steinberg
+    FloydSteinberg,
+    /// Diffusion de l'erreur d'Atkinson
+    Atkinson,
+}
+
+/// Distance utilisée pour choisir la couleur la plus proche de la palette
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Distance {
+    /// Distance euclidienne dans l'espace RGB
+    Euclidienne,
+    /// Distance euclidienne pondérée par la sensibilité de l'œil (0,299 / 0,587 / 0,114)
+    Ponderee,
+    /// Approximation « redmean » de la distance perçue
+    MoyenneRouge,
+}
+
+impl Distance {
+    fn calculer(&self, a: [f32; 3], b: [f32; 3]) -> f32 {
+        let dr = a[0] - b[0];
+        let dg = a[1] - b[1];
+        let db = a[2] - b[2];
+        match self {
+            Distance::Euclidienne => dr * dr + dg * dg + db * db,
+            Distance::Ponderee => 0.299 * dr * dr + 0.587 * dg * dg + 0.114 * db * db,
+            Distance::MoyenneRouge => {
+                let moyenne_rouge = (a[0] + b[0]) / 2.;
+                (2. + moyenne_rouge / 256.) * dr * dr
+                    + 4. * dg * dg
+                    + (2. + (255. - moyenne_rouge) / 256.) * db * db
+            }
+        }
+    }
+}
+
+/// Ajustement d'une image aux dimensions de l'écran
+#[cfg(feature = "image")]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Ajustement {
+    /// Image déformée pour occuper tout l'écran
+    Etirer,
+    /// Image entière, agrandie ou réduite en conservant ses proportions et entourée de blanc
+    #[default]
+    Contenir,
+    /// Image agrandie ou réduite en conservant ses proportions pour couvrir tout l'écran,
+    /// les bords qui dépassent sont rognés
+    Couvrir,
+    /// Image à sa taille d'origine, centrée, rognée si elle dépasse et entourée de blanc sinon
+    Centrer,
+}
+
+#[cfg(feature = "image")]
+impl Ajustement {
+    /// Redimensionner l'image et la centrer dans une image transparente aux dimensions voulues
+    pub fn appliquer(&self, image: &DynamicImage, largeur: u32, hauteur: u32) -> image::RgbaImage {
+        let mut resultat = image::RgbaImage::new(largeur, hauteur);
+        if image.width() == 0 || image.height() == 0 {
+            return resultat;
+        }
+
+        let echelle_x = largeur as f64 / image.width() as f64;
+        let echelle_y = hauteur as f64 / image.height() as f64;
+        let (nouvelle_largeur, nouvelle_hauteur) = match self {
+            Ajustement::Etirer => (largeur, hauteur),
+            Ajustement::Contenir | Ajustement::Couvrir => {
+                let echelle = match self {
+                    Ajustement::Contenir => echelle_x.min(echelle_y),
+                    _ => echelle_x.max(echelle_y),
+                };
+                (
+                    ((image.width() as f64 * echelle).round() as u32).max(1),
+                    ((image.height() as f64 * echelle).round() as u32).max(1),
+                )
+            }
+            Ajustement::Centrer => (image.width(), image.height()),
+        };
+
+        let redimensionnee = if (nouvelle_largeur, nouvelle_hauteur) == (image.width(), image.height())
+        {
+            image.to_rgba8()
+        } else {
+            image
+                .resize_exact(nouvelle_largeur, nouvelle_hauteur, FilterType::Lanczos3)
+                .to_rgba8()
+        };
+        image::imageops::replace(
+            &mut resultat,
+            &redimensionnee,
+            (largeur as i64 - nouvelle_largeur as i64) / 2,
+            (hauteur as i64 - nouvelle_hauteur as i64) / 2,
+        );
+        resultat
+    }
+}
+
+/// Lire un fichier image (PNG, BMP ou PBM/PGM/PPM), le format étant déterminé par son contenu
+#[cfg(feature = "image")]
+pub fn lire_image(chemin: impl AsRef<Path>) -> Result<DynamicImage, ErreurEcran> {
+    Ok(ImageReader::open(chemin)?.with_guessed_format()?.decode()?)
+}
+
+/// Couleurs RGB de référence associées aux couleurs de l'écran
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Palette {
+    /// Blanc
+    pub blanc: [u8; 3],
+    /// Noir
+    pub noir: [u8; 3],
+    /// Rouge (aucun pour un écran noir et blanc)
+    pub rouge: Option<[u8; 3]>,
+}
+
+impl Palette {
+    /// Palette noir et blanc
+    pub fn noir_blanc() -> Self {
+        Self {
+            blanc: [255, 255, 255],
+            noir: [0, 0, 0],
+            rouge: None,
+        }
+    }
+
+    /// Palette noir, blanc et rouge
+    pub fn noir_blanc_rouge() -> Self {
+        Self {
+            rouge: Some([255, 0, 0]),
+            ..Self::noir_blanc()
+        }
+    }
+
+    fn couleurs(&self) -> Vec<(TriCouleur, [f32; 3])> {
+        let mut couleurs = vec![
+            (TriCouleur::Blanc, self.blanc.map(f32::from)),
+            (TriCouleur::Noir, self.noir.map(f32::from)),
+        ];
+        if let Some(rouge) = self.rouge {
+            couleurs.push((TriCouleur::Rouge, rouge.map(f32::from)));
+        }
+        couleurs
+    }
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self::noir_blanc_rouge()
+    }
+}
+
+/// Conversion d'une image RGB en plans noir et rouge
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Conversion {
+    /// Algorithme de tramage
+    pub tramage: Tramage,
+    /// Distance entre couleurs
+    pub distance: Distance,
+    /// Palette de l'écran
+    pub palette: Palette,
+}
+
+impl Default for Conversion {
+    fn default() -> Self {
+        Self::new(Tramage::FloydSteinberg)
+    }
+}
+
+impl Conversion {
+    /// Conversion vers la palette noir, blanc et rouge
+    pub fn new(tramage: Tramage) -> Self {
+        Self {
+            tramage,
+            distance: Distance::MoyenneRouge,
+            palette: Palette::default(),
+        }
+    }
+
+    /// Choisir la distance entre couleurs
+    pub fn avec_distance(mut self, distance: Distance) -> Self {
+        self.distance = distance;
+        self
+    }
+
+    /// Choisir la palette
+    pub fn avec_palette(mut self, palette: Palette) -> Self {
+        self.palette = palette;
+        self
+    }
+
+    /// Convertir des pixels RGB (3 octets par pixel)
+    pub fn convertir_rgb(
+        &self,
+        largeur: usize,
+        hauteur: usize,
+        pixels: &[u8],
+    ) -> Result<MemoireTampon, ErreurEcran> {
+        self.convertir_pixels(largeur, hauteur, pixels, 3)
+    }
+
+    /// Convertir des pixels RGBA (4 octets par pixel).
+    /// Les pixels transparents sont mélangés avec du blanc.
+    pub fn convertir_rgba(
+        &self,
+        largeur: usize,
+        hauteur: usize,
+        pixels: &[u8],
+    ) -> Result<MemoireTampon, ErreurEcran> {
+        self.convertir_pixels(largeur, hauteur, pixels, 4)
+    }
+
+    fn convertir_pixels(
+        &self,
+        largeur: usize,
+        hauteur: usize,
+        pixels: &[u8],
+        octets_par_pixel: usize,
+    ) -> Result<MemoireTampon, ErreurEcran> {
+        // Une taille qui dépasse usize ne peut correspondre à aucun tableau de pixels
+        let taille_attendue = largeur
+            .checked_mul(hauteur)
+            .and_then(|taille| taille.checked_mul(octets_par_pixel));
+        if taille_attendue != Some(pixels.len()) {
+            return Err(ErreurEcran::TailleImageInvalide {
+                attendue: taille_attendue.unwrap_or(usize::MAX),
+                recue: pixels.len(),
+            });
+        }
+
+        Ok(self.tramer(largeur, hauteur, Self::valeurs(pixels, octets_par_pixel)))
+    }
+
+    /// Valeurs RGB de chaque pixel, mélangées avec du blanc selon leur transparence
+    fn valeurs(pixels: &[u8], octets_par_pixel: usize) -> Vec<[f32; 3]> {
+        pixels
+            .chunks_exact(octets_par_pixel)
+            .map(|pixel| {
+                let alpha = match octets_par_pixel {
+                    4 => pixel[3] as f32 / 255.,
+                    _ => 1.,
+                };
+                [0, 1, 2].map(|i| pixel[i] as f32 * alpha + 255. * (1. - alpha))
+            })
+            .collect()
+    }
+
+    /// Convertir des luminances sur 8 bits (1 octet par pixel) en 4 niveaux de gris
+    /// ([`Gris4`]). La palette n'est pas utilisée.
+    pub fn convertir_luminance(
+        &self,
+        largeur: usize,
+        hauteur: usize,
+        pixels: &[u8],
+    ) -> Result<MemoireTampon, ErreurEcran> {
+        let taille_attendue = largeur.checked_mul(hauteur);
+        if taille_attendue != Some(pixels.len()) {
+            return Err(ErreurEcran::TailleImageInvalide {
+                attendue: taille_attendue.unwrap_or(usize::MAX),
+                recue: pixels.len(),
+            });
+        }
+
+        Ok(self.tramer_gris(largeur, hauteur, pixels))
+    }
+
+    fn tramer_gris(&self, largeur: usize, hauteur: usize, pixels: &[u8]) -> MemoireTampon {
+        let niveaux = Gris4::NIVEAUX.map(|gris| (gris, [gris.luminance() as f32; 3]));
+        let valeurs = pixels.iter().map(|luminance| [*luminance as f32; 3]).collect();
+        self.tramer_niveaux(largeur, hauteur, valeurs, &niveaux, MemoireTampon::definir_gris)
+    }
+
+    /// Choisir la couleur de la palette la plus proche
+    fn couleur_proche<C>(&self, couleurs: &[(C, [f32; 3])], valeur: [f32; 3]) -> usize {
+        let mut indice = 0;
+        let mut distance_minimale = f32::MAX;
+        for (i, (_, couleur)) in couleurs.iter().enumerate() {
+            let distance = self.distance.calculer(valeur, *couleur);
+            if distance < distance_minimale {
+                distance_minimale = distance;
+                indice = i;
+            }
+        }
+        indice
+    }
+
+    fn tramer(&self, largeur: usize, hauteur: usize, valeurs: Vec<[f32; 3]>) -> MemoireTampon {
+        let couleurs = self.palette.couleurs();
+        self.tramer_niveaux(largeur, hauteur, valeurs, &couleurs, MemoireTampon::definir_pixel)
+    }
+
+    /// Tramer les valeurs RGB avec les couleurs disponibles, chaque pixel étant dessiné avec
+    /// la fonction fournie
+    fn tramer_niveaux<C: Copy>(
+        &self,
+        largeur: usize,
+        hauteur: usize,
+        mut valeurs: Vec<[f32; 3]>,
+        couleurs: &[(C, [f32; 3])],
+        definir: fn(&mut MemoireTampon, usize, usize, C),
+    ) -> MemoireTampon {
+        let diffusion: &[(isize, usize, f32)] = match self.tramage {
+            Tramage::FloydSteinberg => &DIFFUSION_FLOYD_STEINBERG,
+            Tramage::Atkinson => &DIFFUSION_ATKINSON,
+            Tramage::Seuil | Tramage::Bayer => &[],
+        };
+        let mut memoire_tampon = MemoireTampon::new(largeur, hauteur);
+
+        for y in 0..hauteur {
+            for x in 0..largeur {
+                let valeur = valeurs[y * largeur + x].map(|composante| composante.clamp(0., 255.));
+                let valeur_comparee = match self.tramage {
+                    Tramage::Bayer => {
+                        let seuil = (MATRICE_BAYER[y % 4][x % 4] + 0.5) / 16. - 0.5;
+                        valeur.map(|composante| composante + seuil * 255.)
+                    }
+                    _ => valeur,
+                };
+
+                let (couleur, reference) = couleurs[self.couleur_proche(couleurs, valeur_comparee)];
+                definir(&mut memoire_tampon, x, y, couleur);
+
+                for (dx, dy, poids) in diffusion {
+                    let (voisin_x, voisin_y) = (x as isize + dx, y + dy);
+                    if voisin_x < 0 || voisin_x as usize >= largeur || voisin_y >= hauteur {
+                        continue;
+                    }
+                    let voisin = &mut valeurs[voisin_y * largeur + voisin_x as usize];
+                    for i in 0..3 {
+                        voisin[i] += (valeur[i] - reference[i]) * poids;
+                    }
+                }
+            }
+        }
+
+        memoire_tampon
+    }
+
+    /// Convertir une image RGB
+    #[cfg(feature = "image")]
+    pub fn convertir_image(&self, image: &image::RgbImage) -> MemoireTampon {
+        self.tramer(
+            image.width() as usize,
+            image.height() as usize,
+            Self::valeurs(image.as_raw(), 3),
+        )
+    }
+
+    /// Convertir une image RGBA. Les pixels transparents sont mélangés avec du blanc.
+    #[cfg(feature = "image")]
+    pub fn convertir_image_rgba(&self, image: &image::RgbaImage) -> MemoireTampon {
+        self.tramer(
+            image.width() as usize,
+            image.height() as usize,
+            Self::valeurs(image.as_raw(), 4),
+        )
+    }
+
+    /// Convertir une image en niveaux de gris en 4 niveaux de gris ([`Gris4`])
+    #[cfg(feature = "image")]
+    pub fn convertir_image_gris(&self, image: &image::GrayImage) -> MemoireTampon {
+        self.tramer_gris(image.width() as usize, image.height() as usize, image.as_raw())
+    }
+
+    /// Ajuster une image aux dimensions voulues puis la convertir.
+    /// Les parties transparentes et les bords laissés libres par l'ajustement sont blancs.
+    #[cfg(feature = "image")]
+    pub fn convertir_image_ajustee(
+        &self,
+        image: &DynamicImage,
+        largeur: usize,
+        hauteur: usize,
+        ajustement: Ajustement,
+    ) -> MemoireTampon {
+        self.convertir_image_rgba(&ajustement.appliquer(image, largeur as u32, hauteur as u32))
+    }
+
+    /// Ajuster une image aux dimensions voulues puis la convertir en 4 niveaux de gris
+    /// ([`Gris4`]). Les parties transparentes et les bords laissés libres par l'ajustement sont
+    /// blancs.
+    #[cfg(feature = "image")]
+    pub fn convertir_image_ajustee_gris(
+        &self,
+        image: &DynamicImage,
+        largeur: usize,
+        hauteur: usize,
+        ajustement: Ajustement,
+    ) -> MemoireTampon {
+        let image = ajustement.appliquer(image, largeur as u32, hauteur as u32);
+        let luminances: Vec<u8> = Self::valeurs(image.as_raw(), 4)
+            .into_iter()
+            .map(|[r, g, b]| (0.299 * r + 0.587 * g + 0.114 * b).round() as u8)
+            .collect();
+        self.tramer_gris(largeur, hauteur, &luminances)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Dégradé de 4×4 pixels du noir (en haut à gauche) au blanc (en bas à droite)
+    fn degrade() -> Vec<u8> {
+        (0..16).flat_map(|i| [i * 17; 3]).collect()
+    }
+
+    /// Plan noir d'un dégradé converti en noir et blanc, une ligne de 4 pixels par octet
+    fn plan_noir(tramage: Tramage) -> Vec<u8> {
+        let memoire_tampon = Conversion::new(tramage)
+            .avec_distance(Distance::Euclidienne)
+            .avec_palette(Palette::noir_blanc())
+            .convertir_rgb(4, 4, &degrade())
+            .unwrap();
+        assert!(memoire_tampon.plan_rouge().iter().all(|octet| *octet == 0));
+        memoire_tampon.plan_noir().to_vec()
+    }
+
+    #[test]
+    fn tramage_seuil() {
+        assert_eq!(plan_noir(Tramage::Seuil), [0x0F, 0x0F, 0xFF, 0xFF]);
+    }
+
+    #[test]
+    fn tramage_bayer() {
+        assert_eq!(plan_noir(Tramage::Bayer), [0x0F, 0xAF, 0x5F, 0xFF]);
+    }
+
+    #[test]
+    fn tramage_floyd_steinberg() {
+        assert_eq!(plan_noir(Tramage::FloydSteinberg), [0x0F, 0x5F, 0xBF, 0xFF]);
+    }
+
+    #[test]
+    fn tramage_atkinson() {
+        assert_eq!(plan_noir(Tramage::Atkinson), [0x0F, 0x3F, 0xFF, 0xFF]);
+    }
+
+    #[test]
+    fn couleur_rouge_et_transparence() {
+        let pixels = [
+            255, 0, 0, 255, // rouge
+            200, 30, 30, 255, // rouge foncé
+            0, 0, 0, 0, // transparent
+            20, 20, 20, 255, // noir
+        ];
+        let memoire_tampon = Conversion::new(Tramage::Seuil)
+            .convertir_rgba(4, 1, &pixels)
+            .unwrap();
+        let couleurs: Vec<_> = (0..4).filter_map(|x| memoire_tampon.pixel(x, 0)).collect();
+        assert_eq!(
+            couleurs,
+            [
+                TriCouleur::Rouge,
+                TriCouleur::Rouge,
+                TriCouleur::Blanc,
+                TriCouleur::Noir
+            ]
+        );
+    }
+
+    #[test]
+    fn taille_invalide() {
+        let erreur = Conversion::default().convertir_rgb(4, 4, &[0; 47]);
+        assert!(matches!(
+            erreur,
+            Err(ErreurEcran::TailleImageInvalide {
+                attendue: 48,
+                recue: 47
+            })
+        ));
+    }
+
+    #[test]
+    fn taille_hors_limites() {
+        let erreur = Conversion::default().convertir_rgba(usize::MAX / 2, 3, &[0; 4]);
+        assert!(matches!(
+            erreur,
+            Err(ErreurEcran::TailleImageInvalide {
+                attendue: usize::MAX,
+                recue: 4
+            })
+        ));
+        let erreur = Conversion::default().convertir_luminance(usize::MAX, 2, &[0; 4]);
+        assert!(matches!(
+            erreur,
+            Err(ErreurEcran::TailleImageInvalide {
+                attendue: usize::MAX,
+                recue: 4
+            })
+        ));
+    }
+
+    /// Écrire un fichier dans le répertoire temporaire, en le nommant d'après le test
+    #[cfg(feature = "image")]
+    fn fichier_temporaire(nom: &str, contenu: &[u8]) -> std::path::PathBuf {
+        let chemin = std::env::temp_dir().join(format!("{}_{nom}", std::process::id()));
+        std::fs::write(&chemin, contenu).unwrap();
+        chemin
+    }
+
+    /// Image de 4×2 pixels opaques : 2 colonnes noires à gauche, 2 colonnes blanches à droite
+    #[cfg(feature = "image")]
+    fn image_noir_blanc() -> DynamicImage {
+        DynamicImage::ImageRgb8(image::RgbImage::from_fn(4, 2, |x, _| match x < 2 {
+            true => image::Rgb([0; 3]),
+            false => image::Rgb([255; 3]),
+        }))
+    }
+
+    /// Rectangle (x, y, largeur, hauteur) couvert par les pixels opaques
+    #[cfg(feature = "image")]
+    fn zone_opaque(image: &image::RgbaImage) -> (u32, u32, u32, u32) {
+        let opaques: Vec<_> = image
+            .enumerate_pixels()
+            .filter(|(_, _, pixel)| pixel[3] == 255)
+            .map(|(x, y, _)| (x, y))
+            .collect();
+        let x_min = opaques.iter().map(|(x, _)| *x).min().unwrap();
+        let x_max = opaques.iter().map(|(x, _)| *x).max().unwrap();
+        let y_min = opaques.iter().map(|(_, y)| *y).min().unwrap();
+        let y_max = opaques.iter().map(|(_, y)| *y).max().unwrap();
+        assert_eq!(
+            opaques.len() as u32,
+            (x_max - x_min + 1) * (y_max - y_min + 1)
+        );
+        (x_min, y_min, x_max - x_min + 1, y_max - y_min + 1)
+    }
+
+    /// Première colonne plus claire que le gris moyen sur une ligne
+    #[cfg(feature = "image")]
+    fn premiere_colonne_claire(image: &image::RgbaImage, y: u32) -> u32 {
+        (0..image.width())
+            .find(|x| image.get_pixel(*x, y)[0] > 128)
+            .unwrap()
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn lecture_png() {
+        let mut png = std::io::Cursor::new(Vec::new());
+        image_noir_blanc()
+            .write_to(&mut png, image::ImageFormat::Png)
+            .unwrap();
+        let chemin = fichier_temporaire("lecture.png", png.get_ref());
+        let image = lire_image(&chemin);
+        std::fs::remove_file(&chemin).unwrap();
+
+        let image = image.unwrap().to_rgb8();
+        assert_eq!(image.dimensions(), (4, 2));
+        assert_eq!(image.get_pixel(1, 1), &image::Rgb([0; 3]));
+        assert_eq!(image.get_pixel(2, 0), &image::Rgb([255; 3]));
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn lecture_pbm() {
+        // Format binaire : 1 bit par pixel, 1 pour noir, lignes complétées à l'octet
+        let mut pbm = b"P4\n4 2\n".to_vec();
+        pbm.extend([0b1100_0000, 0b1100_0000]);
+        // Extension trompeuse : le format est déterminé par le contenu
+        let chemin = fichier_temporaire("lecture_pbm.png", &pbm);
+        let image = lire_image(&chemin);
+        std::fs::remove_file(&chemin).unwrap();
+
+        let image = image.unwrap().to_rgb8();
+        assert_eq!(image.dimensions(), (4, 2));
+        assert_eq!(image.get_pixel(1, 1), &image::Rgb([0; 3]));
+        assert_eq!(image.get_pixel(2, 0), &image::Rgb([255; 3]));
+
+        let memoire_tampon = Conversion::new(Tramage::Seuil).convertir_image_ajustee(
+            &DynamicImage::ImageRgb8(image),
+            4,
+            2,
+            Ajustement::Etirer,
+        );
+        // Bits de remplissage blancs en fin de ligne
+        assert_eq!(memoire_tampon.plan_noir(), [0b0011_1111, 0b0011_1111]);
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn ajustement_etirer() {
+        let image = Ajustement::Etirer.appliquer(&image_noir_blanc(), 8, 8);
+        assert_eq!(image.dimensions(), (8, 8));
+        assert_eq!(zone_opaque(&image), (0, 0, 8, 8));
+        assert!((0..8).all(|y| premiere_colonne_claire(&image, y) == 4));
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn ajustement_contenir() {
+        // Échelle 2 : 8×4 pixels, centrés verticalement
+        let image = Ajustement::Contenir.appliquer(&image_noir_blanc(), 8, 8);
+        assert_eq!(image.dimensions(), (8, 8));
+        assert_eq!(zone_opaque(&image), (0, 2, 8, 4));
+        assert!((2..6).all(|y| premiere_colonne_claire(&image, y) == 4));
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn ajustement_couvrir() {
+        // Échelle 4 : 16×8 pixels, dont 4 colonnes rognées de chaque côté
+        let image = Ajustement::Couvrir.appliquer(&image_noir_blanc(), 8, 8);
+        assert_eq!(image.dimensions(), (8, 8));
+        assert_eq!(zone_opaque(&image), (0, 0, 8, 8));
+        // La limite entre le noir et le blanc, au milieu de l'image, reste au milieu de la zone
+        assert!((0..8).all(|y| premiere_colonne_claire(&image, y) == 4));
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn ajustement_centrer() {
+        let image = Ajustement::Centrer.appliquer(&image_noir_blanc(), 8, 8);
+        assert_eq!(image.dimensions(), (8, 8));
+        assert_eq!(zone_opaque(&image), (2, 3, 4, 2));
+        assert_eq!(image.get_pixel(3, 3), &image::Rgba([0, 0, 0, 255]));
+        assert_eq!(image.get_pixel(4, 4), &image::Rgba([255, 255, 255, 255]));
+
+        // Image plus grande que la zone : rognée de chaque côté
+        let image = Ajustement::Centrer.appliquer(&image_noir_blanc(), 2, 2);
+        assert_eq!(zone_opaque(&image), (0, 0, 2, 2));
+        assert_eq!(image.get_pixel(0, 0), &image::Rgba([0, 0, 0, 255]));
+        assert_eq!(image.get_pixel(1, 0), &image::Rgba([255, 255, 255, 255]));
+    }
+}
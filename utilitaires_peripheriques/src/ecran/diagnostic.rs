@@ -0,0 +1,535 @@
+use std::{fmt, ops::RangeInclusive};
+
+use tokio::time::{sleep, Duration};
+
+use crate::ecran::{
+    conversion::MATRICE_BAYER,
+    ecran::Ecran,
+    erreur::EtapeEcran,
+    materiel::{BrocheEntree, BrocheSortie, LectureSpi, LiaisonSpi},
+    memoire_tampon::{MemoireTampon, TriCouleur},
+    modele::{ModeleEcran, PlansCouleur},
+};
+
+/// Chiffres de 3×5 pixels utilisés pour les coordonnées de la grille, une ligne par octet
+/// (bit 2 à gauche), suivis de la virgule
+const CHIFFRES: [[u8; 5]; 11] = [
+    [0b111, 0b101, 0b101, 0b101, 0b111],
+    [0b010, 0b110, 0b010, 0b010, 0b111],
+    [0b111, 0b001, 0b111, 0b100, 0b111],
+    [0b111, 0b001, 0b111, 0b001, 0b111],
+    [0b101, 0b101, 0b111, 0b001, 0b001],
+    [0b111, 0b100, 0b111, 0b001, 0b111],
+    [0b111, 0b100, 0b111, 0b101, 0b111],
+    [0b111, 0b001, 0b001, 0b001, 0b001],
+    [0b111, 0b101, 0b111, 0b101, 0b111],
+    [0b111, 0b101, 0b111, 0b001, 0b111],
+    [0b000, 0b000, 0b000, 0b010, 0b100],
+];
+
+/// Agrandissement des chiffres des coordonnées
+const ECHELLE_CHIFFRES: usize = 2;
+
+/// Motif de test affiché sur tout l'écran
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Motif {
+    /// Ecran entièrement blanc
+    Blanc,
+    /// Ecran entièrement noir
+    Noir,
+    /// Ecran entièrement rouge, ignoré par les écrans noir et blanc
+    Rouge,
+    /// Damier de cases noires et blanches
+    Damier {
+        /// Côté d'une case en pixels
+        cote: usize,
+    },
+    /// Bandes verticales de densité croissante, en noir en haut et en rouge en bas
+    Degrade {
+        /// Nombre de bandes, du blanc à la couleur pleine
+        bandes: usize,
+    },
+    /// Grille de lignes noires avec les coordonnées des intersections et un cadre rouge sur
+    /// les bords de l'écran
+    Grille {
+        /// Espacement des lignes en pixels. Les coordonnées sont écrites une ligne sur deux.
+        pas: usize,
+    },
+}
+
+impl Motif {
+    /// Motifs affichés par défaut
+    pub const SEQUENCE_DEFAUT: [Motif; 6] = [
+        Motif::Blanc,
+        Motif::Noir,
+        Motif::Rouge,
+        Motif::Damier { cote: 8 },
+        Motif::Degrade { bandes: 8 },
+        Motif::Grille { pas: 50 },
+    ];
+
+    /// Dessiner le motif sur toute la mémoire tampon. Le rouge est remplacé par du noir pour
+    /// les écrans noir et blanc.
+    pub fn dessiner(&self, memoire_tampon: &mut MemoireTampon, plans_couleur: PlansCouleur) {
+        let (largeur, hauteur) = (memoire_tampon.largeur(), memoire_tampon.hauteur());
+        let rouge = match plans_couleur {
+            PlansCouleur::NoirRouge => TriCouleur::Rouge,
+            PlansCouleur::Noir => TriCouleur::Noir,
+        };
+        match *self {
+            Motif::Blanc => memoire_tampon.effacer(TriCouleur::Blanc),
+            Motif::Noir => memoire_tampon.effacer(TriCouleur::Noir),
+            Motif::Rouge => memoire_tampon.effacer(rouge),
+            Motif::Damier { cote } => {
+                let cote = cote.max(1);
+                for y in 0..hauteur {
+                    for x in 0..largeur {
+                        let couleur = match (x / cote + y / cote) % 2 {
+                            0 => TriCouleur::Noir,
+                            _ => TriCouleur::Blanc,
+                        };
+                        memoire_tampon.definir_pixel(x, y, couleur);
+                    }
+                }
+            }
+            Motif::Degrade { bandes } => {
+                let bandes = bandes.max(2);
+                for y in 0..hauteur {
+                    let couleur = match y < hauteur / 2 {
+                        true => TriCouleur::Noir,
+                        false => rouge,
+                    };
+                    for x in 0..largeur {
+                        let densite = (x * bandes / largeur) as f32 / (bandes - 1) as f32;
+                        let seuil = (MATRICE_BAYER[y % 4][x % 4] + 0.5) / 16.;
+                        let couleur = match seuil < densite {
+                            true => couleur,
+                            false => TriCouleur::Blanc,
+                        };
+                        memoire_tampon.definir_pixel(x, y, couleur);
+                    }
+                }
+            }
+            Motif::Grille { pas } => {
+                let pas = pas.max(1);
+                memoire_tampon.effacer(TriCouleur::Blanc);
+                for y in 0..hauteur {
+                    for x in 0..largeur {
+                        if x % pas == 0 || y % pas == 0 {
+                            memoire_tampon.definir_pixel(x, y, TriCouleur::Noir);
+                        }
+                        if x == 0 || y == 0 || x == largeur - 1 || y == hauteur - 1 {
+                            memoire_tampon.definir_pixel(x, y, rouge);
+                        }
+                    }
+                }
+                for y in (0..hauteur).step_by(2 * pas) {
+                    for x in (0..largeur).step_by(2 * pas) {
+                        ecrire_coordonnees(memoire_tampon, x + 3, y + 3, &format!("{x},{y}"));
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Display for Motif {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Motif::Blanc => write!(f, "blanc"),
+            Motif::Noir => write!(f, "noir"),
+            Motif::Rouge => write!(f, "rouge"),
+            Motif::Damier { cote } => write!(f, "damier ({cote} px)"),
+            Motif::Degrade { bandes } => write!(f, "dégradé ({bandes} bandes)"),
+            Motif::Grille { pas } => write!(f, "grille ({pas} px)"),
+        }
+    }
+}
+
+/// Ecrire des coordonnées avec les chiffres de 3×5 pixels
+fn ecrire_coordonnees(memoire_tampon: &mut MemoireTampon, x: usize, y: usize, texte: &str) {
+    let avance = (3 + 1) * ECHELLE_CHIFFRES;
+    for (rang, caractere) in texte.chars().enumerate() {
+        let indice = match caractere {
+            ',' => 10,
+            _ => caractere.to_digit(10).unwrap_or(0) as usize,
+        };
+        for (ligne, bits) in CHIFFRES[indice].iter().enumerate() {
+            for colonne in 0..3 {
+                if bits & (0b100 >> colonne) == 0 {
+                    continue;
+                }
+                for dy in 0..ECHELLE_CHIFFRES {
+                    for dx in 0..ECHELLE_CHIFFRES {
+                        memoire_tampon.definir_pixel(
+                            x + rang * avance + colonne * ECHELLE_CHIFFRES + dx,
+                            y + ligne * ECHELLE_CHIFFRES + dy,
+                            TriCouleur::Noir,
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Durées d'occupation attendues de la broche BUSY pour chaque étape
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DureesAttendues {
+    /// Mise sous tension lors de l'initialisation
+    pub initialisation: RangeInclusive<Duration>,
+    /// Rafraîchissement de l'écran
+    pub rafraichissement: RangeInclusive<Duration>,
+    /// Mise hors tension
+    pub extinction: RangeInclusive<Duration>,
+}
+
+impl DureesAttendues {
+    /// Durées habituelles de l'écran : un rafraîchissement dure une quinzaine de secondes en
+    /// trois couleurs, quelques secondes en noir et blanc ou avec une table de formes d'onde
+    pub fn pour_ecran<M, S, O, I>(ecran: &Ecran<M, S, O, I>) -> Self
+    where
+        M: ModeleEcran,
+        S: LiaisonSpi,
+        O: BrocheSortie,
+        I: BrocheEntree,
+    {
+        let rafraichissement = match (M::PLANS_COULEUR, ecran.table_forme()) {
+            (PlansCouleur::NoirRouge, None) => Duration::from_secs(8)..=Duration::from_secs(45),
+            (PlansCouleur::Noir, None) => Duration::from_secs(1)..=Duration::from_secs(15),
+            (_, Some(_)) => Duration::from_millis(500)..=Duration::from_secs(15),
+        };
+        Self {
+            initialisation: Duration::ZERO..=Duration::from_secs(5),
+            rafraichissement,
+            extinction: Duration::ZERO..=Duration::from_secs(5),
+        }
+    }
+
+    /// Durées attendues pour une étape, aucune pour les attentes demandées par le programme et la
+    /// mesure de la température
+    fn etape(&self, etape: EtapeEcran) -> Option<&RangeInclusive<Duration>> {
+        match etape {
+            EtapeEcran::Initialisation => Some(&self.initialisation),
+            EtapeEcran::Rafraichissement => Some(&self.rafraichissement),
+            EtapeEcran::Extinction => Some(&self.extinction),
+            EtapeEcran::Attente | EtapeEcran::MesureTemperature => None,
+        }
+    }
+}
+
+/// Anomalie détectée pendant le diagnostic
+#[derive(Clone, Debug, PartialEq)]
+pub enum Anomalie {
+    /// La broche BUSY est restée occupée moins longtemps que prévu
+    AttenteTropCourte {
+        /// Etape pendant laquelle le contrôleur était occupé
+        etape: EtapeEcran,
+        /// Durée mesurée
+        duree: Duration,
+        /// Durée minimale attendue
+        minimum: Duration,
+    },
+    /// La broche BUSY est restée occupée plus longtemps que prévu
+    AttenteTropLongue {
+        /// Etape pendant laquelle le contrôleur était occupé
+        etape: EtapeEcran,
+        /// Durée mesurée
+        duree: Duration,
+        /// Durée maximale attendue
+        maximum: Duration,
+    },
+    /// Température impossible à lire
+    TemperatureIllisible(String),
+    /// Erreur renvoyée par le pilote, qui interrompt le diagnostic
+    Erreur(String),
+}
+
+impl fmt::Display for Anomalie {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Anomalie::AttenteTropCourte {
+                etape,
+                duree,
+                minimum,
+            } => write!(
+                f,
+                "occupation trop courte ({etape}) : {:.1} s, au moins {:.1} s attendues \
+                 (broche BUSY mal reliée ou contrôleur ne recevant pas les commandes ?)",
+                duree.as_secs_f32(),
+                minimum.as_secs_f32()
+            ),
+            Anomalie::AttenteTropLongue {
+                etape,
+                duree,
+                maximum,
+            } => write!(
+                f,
+                "occupation trop longue ({etape}) : {:.1} s, au plus {:.1} s attendues",
+                duree.as_secs_f32(),
+                maximum.as_secs_f32()
+            ),
+            Anomalie::TemperatureIllisible(erreur) => {
+                write!(f, "température illisible : {erreur}")
+            }
+            Anomalie::Erreur(erreur) => write!(f, "erreur : {erreur}"),
+        }
+    }
+}
+
+/// Résultat de l'affichage d'un motif
+#[derive(Clone, Debug, PartialEq)]
+pub struct MesureMotif {
+    /// Motif affiché
+    pub motif: Motif,
+    /// Durée d'occupation de la broche BUSY pour chaque attente, dans l'ordre
+    pub attentes: Vec<(EtapeEcran, Duration)>,
+    /// Anomalies détectées
+    pub anomalies: Vec<Anomalie>,
+}
+
+/// Rapport du diagnostic d'un écran
+///
+/// Les défauts visibles (lignes mortes, plan rouge absent, pixels bloqués) ne peuvent pas être
+/// détectés par le pilote : les motifs doivent être examinés pendant le diagnostic.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RapportDiagnostic {
+    /// Nom du modèle d'écran
+    pub modele: &'static str,
+    /// Température lue au début du diagnostic en °C
+    pub temperature: Option<f32>,
+    /// Anomalies détectées avant l'affichage des motifs
+    pub anomalies: Vec<Anomalie>,
+    /// Résultat de chaque motif affiché
+    pub mesures: Vec<MesureMotif>,
+}
+
+impl RapportDiagnostic {
+    /// Indiquer si aucune anomalie n'a été détectée
+    pub fn est_conforme(&self) -> bool {
+        self.nombre_anomalies() == 0
+    }
+
+    /// Nombre total d'anomalies détectées
+    pub fn nombre_anomalies(&self) -> usize {
+        self.anomalies.len()
+            + self
+                .mesures
+                .iter()
+                .map(|mesure| mesure.anomalies.len())
+                .sum::<usize>()
+    }
+}
+
+impl fmt::Display for RapportDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Diagnostic de l'écran {}", self.modele)?;
+        match self.temperature {
+            Some(temperature) => writeln!(f, " ({temperature:.1} °C)")?,
+            None => writeln!(f)?,
+        }
+        for anomalie in &self.anomalies {
+            writeln!(f, "  ! {anomalie}")?;
+        }
+        for mesure in &self.mesures {
+            let attentes: Vec<String> = mesure
+                .attentes
+                .iter()
+                .map(|(etape, duree)| format!("{etape} {:.1} s", duree.as_secs_f32()))
+                .collect();
+            writeln!(f, "- {} : {}", mesure.motif, attentes.join(", "))?;
+            for anomalie in &mesure.anomalies {
+                writeln!(f, "  ! {anomalie}")?;
+            }
+        }
+        match self.nombre_anomalies() {
+            0 => write!(f, "Aucune anomalie détectée"),
+            nombre => write!(f, "{nombre} anomalie(s) détectée(s)"),
+        }
+    }
+}
+
+/// Diagnostic d'un écran : affichage de motifs de test et mesure des durées d'occupation
+///
+/// Chaque motif est affiché par un cycle complet d'initialisation, de rafraîchissement et de
+/// mise en veille, afin de vérifier les broches RST, DC, CS et BUSY à chaque étape.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diagnostic {
+    motifs: Vec<Motif>,
+    durees: Option<DureesAttendues>,
+    pause: Duration,
+}
+
+impl Default for Diagnostic {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Diagnostic {
+    /// Diagnostic avec les motifs par défaut, affichés chacun pendant 10 s
+    pub fn new() -> Self {
+        Self {
+            motifs: Motif::SEQUENCE_DEFAUT.to_vec(),
+            durees: None,
+            pause: Duration::from_secs(10),
+        }
+    }
+
+    /// Choisir les motifs affichés successivement
+    pub fn avec_motifs(mut self, motifs: Vec<Motif>) -> Self {
+        self.motifs = motifs;
+        self
+    }
+
+    /// Choisir les durées d'occupation attendues, à la place de celles de
+    /// [`DureesAttendues::pour_ecran`]
+    pub fn avec_durees(mut self, durees: DureesAttendues) -> Self {
+        self.durees = Some(durees);
+        self
+    }
+
+    /// Choisir la durée d'affichage de chaque motif avant le suivant
+    pub fn avec_pause(mut self, pause: Duration) -> Self {
+        self.pause = pause;
+        self
+    }
+
+    /// Afficher les motifs et mesurer les durées d'occupation.
+    /// Le diagnostic s'arrête à la première erreur du pilote. La mémoire tampon et le maintien
+    /// sous tension de l'écran sont restaurés ensuite, sans rafraîchir l'écran.
+    pub async fn executer<M, S, O, I>(&self, ecran: &mut Ecran<M, S, O, I>) -> RapportDiagnostic
+    where
+        M: ModeleEcran,
+        S: LectureSpi,
+        O: BrocheSortie,
+        I: BrocheEntree,
+    {
+        let durees = self
+            .durees
+            .clone()
+            .unwrap_or_else(|| DureesAttendues::pour_ecran(ecran));
+        let mut rapport = RapportDiagnostic {
+            modele: M::NOM,
+            temperature: None,
+            anomalies: Vec::new(),
+            mesures: Vec::new(),
+        };
+
+        log::info!("Diagnostic de l'écran {}", M::NOM);
+        let memoire_tampon = ecran.memoire_tampon().clone();
+        let maintien_actif = ecran.maintien_actif();
+        ecran.definir_maintien_actif(false);
+
+        match ecran.lire_temperature().await {
+            Ok(temperature) => rapport.temperature = Some(temperature),
+            Err(erreur) => rapport
+                .anomalies
+                .push(Anomalie::TemperatureIllisible(erreur.to_string())),
+        }
+
+        for (rang, motif) in self.motifs.iter().enumerate() {
+            if *motif == Motif::Rouge && M::PLANS_COULEUR == PlansCouleur::Noir {
+                continue;
+            }
+            if rang > 0 {
+                sleep(self.pause).await;
+            }
+
+            log::info!("Motif {motif}");
+            motif.dessiner(ecran.memoire_tampon_mut(), M::PLANS_COULEUR);
+            ecran.demarrer_mesure_attentes();
+            let resultat = ecran.forcer_mise_a_jour().await;
+            let mut mesure = MesureMotif {
+                motif: *motif,
+                attentes: ecran.terminer_mesure_attentes(),
+                anomalies: Vec::new(),
+            };
+
+            for (etape, duree) in &mesure.attentes {
+                let Some(attendue) = durees.etape(*etape) else {
+                    continue;
+                };
+                if duree < attendue.start() {
+                    mesure.anomalies.push(Anomalie::AttenteTropCourte {
+                        etape: *etape,
+                        duree: *duree,
+                        minimum: *attendue.start(),
+                    });
+                } else if duree > attendue.end() {
+                    mesure.anomalies.push(Anomalie::AttenteTropLongue {
+                        etape: *etape,
+                        duree: *duree,
+                        maximum: *attendue.end(),
+                    });
+                }
+            }
+            let interrompu = resultat.is_err();
+            if let Err(erreur) = resultat {
+                mesure.anomalies.push(Anomalie::Erreur(erreur.to_string()));
+            }
+            for anomalie in &mesure.anomalies {
+                log::warn!("Motif {motif} : {anomalie}");
+            }
+            rapport.mesures.push(mesure);
+            if interrompu {
+                break;
+            }
+        }
+
+        *ecran.memoire_tampon_mut() = memoire_tampon;
+        ecran.definir_maintien_actif(maintien_actif);
+        log::info!(
+            "Diagnostic terminé : {} anomalie(s)",
+            rapport.nombre_anomalies()
+        );
+        rapport
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecran::{erreur::ErreurEcran, simulation::ControleurSimule};
+
+    #[tokio::test(start_paused = true)]
+    async fn attente_interrompue_par_le_delai() {
+        let controleur = ControleurSimule::new();
+        let mut ecran = controleur.ecran();
+        ecran.definir_delai_occupation(Duration::from_secs(1));
+        controleur.definir_duree_occupation(50);
+        let diagnostic = Diagnostic::new()
+            .avec_motifs(vec![Motif::Blanc, Motif::Noir])
+            .avec_durees(DureesAttendues {
+                initialisation: Duration::ZERO..=Duration::from_millis(500),
+                rafraichissement: Duration::ZERO..=Duration::from_secs(1),
+                extinction: Duration::ZERO..=Duration::from_millis(500),
+            });
+
+        let rapport = diagnostic.executer(&mut ecran).await;
+
+        // L'attente interrompue est mesurée et le diagnostic s'arrête au premier motif
+        assert_eq!(rapport.mesures.len(), 1);
+        let mesure = &rapport.mesures[0];
+        assert_eq!(
+            mesure.attentes,
+            vec![(EtapeEcran::Initialisation, Duration::from_secs(1))]
+        );
+        assert_eq!(
+            mesure.anomalies,
+            vec![
+                Anomalie::AttenteTropLongue {
+                    etape: EtapeEcran::Initialisation,
+                    duree: Duration::from_secs(1),
+                    maximum: Duration::from_millis(500),
+                },
+                Anomalie::Erreur(
+                    ErreurEcran::DelaiDepasse {
+                        etape: EtapeEcran::Initialisation
+                    }
+                    .to_string()
+                ),
+            ]
+        );
+    }
+}
@@ -1,71 +1,451 @@
-use std::cmp;
+use std::{
+    cmp,
+    marker::PhantomData,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
 
 use rppal::{
     gpio::{InputPin, OutputPin, Gpio},
-    spi::{Spi, SlaveSelect, Bus, Mode, Error},
+    spi::{Spi, SlaveSelect, Bus, Mode},
 };
-use tokio::{time::sleep,time::Duration};
+use flume::Receiver;
+use tokio::{time::sleep,time::timeout,time::Duration,time::Instant};
 
+use crate::ecran::alimentation::EtatAlimentation;
+use crate::ecran::conversion::Conversion;
+#[cfg(feature = "image")]
+use crate::ecran::conversion::{lire_image, Ajustement};
+use crate::ecran::erreur::{ErreurEcran, EtapeEcran};
+use crate::ecran::image_enregistree::ImageEnregistree;
 use crate::ecran::instruction::Instruction;
+use crate::ecran::materiel::{BrocheEntree, BrocheSortie, LectureSpi, LiaisonSpi};
+use crate::ecran::memoire_tampon::{MemoireTampon, TriCouleur};
+use crate::ecran::modele::{
+    Etape, Modele4In2B, Modele7In5BV2, Modele7In5V2, ModeleEcran, Plan, PlansCouleur,
+    TransmissionPlan, TRANSMISSION_GRIS_4, TRANSMISSION_NOIR_BLANC,
+};
+use crate::ecran::rotation::Rotation;
+#[cfg(feature = "svg")]
+use crate::ecran::svg::ImageSvg;
+use crate::ecran::table_forme::{
+    TableForme, GRIS_CASCADE, GRIS_DEMARRAGE_CONVERTISSEUR, GRIS_TEMPERATURE,
+};
+use crate::ecran::temperature::{self, ControleTemperature};
+use crate::ecran::zone::Zone;
+
+/// Délai maximal d'attente de la disponibilité du contrôleur par défaut
+pub const DELAI_OCCUPATION_DEFAUT: Duration = Duration::from_secs(100);
+
+/// Intervalle entre deux lectures de la broche BUSY
+const INTERVALLE_OCCUPATION: Duration = Duration::from_millis(100);
+
+/// Intervalle maximal entre deux lectures de la broche BUSY lorsque les interruptions sont
+/// utilisées, au cas où un front serait manqué
+const INTERVALLE_OCCUPATION_INTERRUPTION: Duration = Duration::from_secs(1);
+
+/// Résultat d'une mise à jour de l'écran
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum MiseAJour {
+    /// L'image a été transmise et l'écran rafraîchi
+    Effectuee,
+    /// L'image est identique à celle affichée : l'écran n'a pas été rafraîchi
+    Inchangee,
+}
+
+/// Méthode d'attente de la disponibilité du contrôleur
+enum SurveillanceOccupation {
+    /// Pas encore déterminée
+    NonDemarree,
+    /// Interruption sur les fronts de la broche BUSY
+    Interruption(Receiver<()>),
+    /// Lecture de la broche BUSY toutes les 100 ms
+    Scrutation,
+}
+
+/// Ecran à encre électronique 7.5" noir, blanc et rouge (voir [`Modele7In5BV2`])
+pub type Wepd7In5BV2<S = Spi, O = OutputPin, I = InputPin> = Ecran<Modele7In5BV2, S, O, I>;
+
+/// Ecran à encre électronique 7.5" noir et blanc (voir [`Modele7In5V2`])
+pub type Wepd7In5V2<S = Spi, O = OutputPin, I = InputPin> = Ecran<Modele7In5V2, S, O, I>;
+
+/// Ecran à encre électronique 4.2" noir, blanc et rouge (voir [`Modele4In2B`])
+pub type Wepd4In2B<S = Spi, O = OutputPin, I = InputPin> = Ecran<Modele4In2B, S, O, I>;
+
+/// Ecran à encre électronique Waveshare
+///
+/// Le pilote est générique sur le modèle d'écran, la liaison SPI et les broches utilisées.
+/// Par défaut, il utilise les périphériques du Raspberry Pi (`rppal`). Voir
+/// [`crate::ecran::simulation`] pour un contrôleur simulé.
+pub struct Ecran<M, S = Spi, O = OutputPin, I = InputPin> {
+    modele: PhantomData<M>,
+    spi: S,
+    dc: O,
+    rst: O,
+    cs: O,
+    busy: I,
+    memoire_tampon: MemoireTampon,
+    delai_occupation: Duration,
+    surveillance_occupation: SurveillanceOccupation,
+    etat_alimentation: EtatAlimentation,
+    maintien_actif: bool,
+    controle_temperature: ControleTemperature,
+    derniere_temperature: Option<f32>,
+    table_forme: Option<TableForme>,
+    image_affichee: Option<MemoireTampon>,
+    fichier_image_affichee: Option<PathBuf>,
+    attentes_mesurees: Option<Vec<(EtapeEcran, Duration)>>,
+}
+
+impl<M: ModeleEcran> Ecran<M> {
+    /// Largeur physique de l'écran, sans rotation
+    pub const LARGEUR: usize = M::LARGEUR;
+
+    /// Hauteur physique de l'écran, sans rotation
+    pub const HAUTEUR: usize = M::HAUTEUR;
 
-const DISPLAY_WIDTH: usize = 800;
-const DISPLAY_HEIGHT: usize = 480;
-const BUFFER_SIZE: usize = DISPLAY_WIDTH * DISPLAY_HEIGHT / 8;
-
-/// Ecran à encre électronique - 7.5inch E-Ink display
-/// Modèle : [`800×480, 7.5inch E-Ink display HAT for Raspberry Pi`](https://www.waveshare.com/7.5inch-e-paper-hat.htm)
-/// Implémentation python officielle : [epd7in5b_V2.py](https://github.com/waveshare/e-Paper/blob/master/RaspberryPi_JetsonNano/python/lib/waveshare_epd/epd7in5b_V2.py)
-pub struct Wepd7In5BV2 {
-    spi: Spi,
-    dc: OutputPin,
-    rst: OutputPin,
-    cs: OutputPin,
-    busy: InputPin,
-    buffer_red: [u8; BUFFER_SIZE],
-    buffer_black: [u8; BUFFER_SIZE],
+    /// Ecran relié aux périphériques du Raspberry Pi
+    pub fn new(spi_bus:Bus,dc_numero_pin: u8, rst_numero_pin: u8, cs_numero_pin: u8, busy_numero_pin: u8) -> Result<Self,ErreurEcran> {
+
+        let spi = Spi::new(spi_bus, SlaveSelect::Ss0, 4000000, Mode::Mode0)
+            .map_err(ErreurEcran::spi)?;
+        let gpio = Gpio::new()?;
+        let rst = gpio.get(rst_numero_pin)?.into_output();
+        let dc = gpio.get(dc_numero_pin)?.into_output();
+        let cs = gpio.get(cs_numero_pin)?.into_output();
+        let busy = gpio.get(busy_numero_pin)?.into_input();
+
+        Ok(Self::avec_materiel(spi, dc, rst, cs, busy))
+    }
 }
 
-impl Wepd7In5BV2 {
-    /// Ecran Wepd7In5BV2
-    pub fn new(spi_bus:Bus,dc_numero_pin: u8, rst_numero_pin: u8, cs_numero_pin: u8, busy_numero_pin: u8) -> Result<Self,Error> {
+impl<M: ModeleEcran, S: LiaisonSpi, O: BrocheSortie, I: BrocheEntree> Ecran<M, S, O, I> {
+    /// Ecran utilisant la liaison SPI et les broches fournies
+    pub fn avec_materiel(spi: S, dc: O, rst: O, cs: O, busy: I) -> Self {
+        Self {
+            modele: PhantomData,
+            spi,
+            dc,
+            rst,
+            cs,
+            busy,
+            memoire_tampon: MemoireTampon::new(M::LARGEUR, M::HAUTEUR),
+            delai_occupation: DELAI_OCCUPATION_DEFAUT,
+            surveillance_occupation: SurveillanceOccupation::NonDemarree,
+            etat_alimentation: EtatAlimentation::Eteint,
+            maintien_actif: false,
+            controle_temperature: ControleTemperature::default(),
+            derniere_temperature: None,
+            table_forme: None,
+            image_affichee: None,
+            fichier_image_affichee: None,
+            attentes_mesurees: None,
+        }
+    }
+
+    /// Oublier l'image affichée : la prochaine mise à jour rafraîchit l'écran même si la
+    /// mémoire tampon n'a pas changé
+    pub fn oublier_image_affichee(&mut self) {
+        self.image_affichee = None;
+    }
+
+    /// Dernière image affichée par l'écran, si elle est connue
+    pub fn image_affichee(&self) -> Option<&MemoireTampon> {
+        self.image_affichee.as_ref()
+    }
+
+    /// Fichier dans lequel la dernière image affichée est enregistrée
+    pub fn fichier_image_affichee(&self) -> Option<&Path> {
+        self.fichier_image_affichee.as_deref()
+    }
+
+    /// Enregistrer la dernière image affichée dans un fichier après chaque rafraîchissement,
+    /// ou ne pas l'enregistrer (`None`)
+    pub fn definir_fichier_image_affichee(&mut self, fichier: Option<PathBuf>) {
+        self.fichier_image_affichee = fichier;
+    }
+
+    /// Restaurer l'image affichée enregistrée dans un fichier, par exemple au redémarrage :
+    /// les mises à jour d'une image identique seront ignorées.
+    /// Renvoie la date de l'affichage de l'image.
+    pub fn restaurer_image_affichee(
+        &mut self,
+        chemin: impl AsRef<Path>,
+    ) -> Result<SystemTime, ErreurEcran> {
+        let image_enregistree = ImageEnregistree::lire_modele::<M>(chemin)?;
+        let horodatage = image_enregistree.horodatage();
+        let mut image_affichee = image_enregistree.into_memoire_tampon();
+        image_affichee.definir_rotation(self.rotation());
+        image_affichee.definir_miroir(self.miroir());
+        self.image_affichee = Some(image_affichee);
+        Ok(horodatage)
+    }
+
+    /// Enregistrer la dernière image affichée si un fichier est défini.
+    /// Le rafraîchissement ayant réussi, une erreur d'écriture est seulement signalée.
+    fn enregistrer_image_affichee(&self) {
+        if let (Some(fichier), Some(image_affichee)) =
+            (&self.fichier_image_affichee, &self.image_affichee)
+        {
+            let image_enregistree = ImageEnregistree::new(M::NOM, image_affichee.clone());
+            if let Err(erreur) = image_enregistree.ecrire(fichier) {
+                log::warn!(
+                    "Impossible d'enregistrer l'image affichée dans {} : {erreur}",
+                    fichier.display()
+                );
+            }
+        }
+    }
+
+    /// Indiquer si la zone physique de la mémoire tampon est identique à l'image affichée
+    fn est_affichee(&self, zone: &Zone) -> bool {
+        match &self.image_affichee {
+            Some(image_affichee) => {
+                image_affichee.extraire_zone(zone) == self.memoire_tampon.extraire_zone(zone)
+            }
+            None => false,
+        }
+    }
+
+    /// Remettre l'écran en veille profonde sans rafraîchissement, s'il a été initialisé et que
+    /// le maintien sous tension n'est pas activé
+    async fn terminer_sans_rafraichissement(&mut self) -> Result<MiseAJour, ErreurEcran> {
+        log::debug!("Image inchangée, pas de rafraîchissement");
+        if self.etat_alimentation == EtatAlimentation::Actif && !self.maintien_actif {
+            self.eteindre().await?;
+        }
+        Ok(MiseAJour::Inchangee)
+    }
+
+    /// Table de formes d'onde chargée lors de l'initialisation
+    pub fn table_forme(&self) -> Option<&TableForme> {
+        self.table_forme.as_ref()
+    }
+
+    /// Définir la table de formes d'onde chargée lors de l'initialisation, ou utiliser les
+    /// formes d'onde de la mémoire OTP du contrôleur (`None`).
+    /// Si l'écran est sous tension, il est initialisé de nouveau lors de la prochaine mise à jour.
+    pub fn definir_table_forme(&mut self, table_forme: Option<TableForme>) {
+        if table_forme.as_ref().is_some_and(TableForme::est_gris)
+            && M::PLANS_COULEUR != PlansCouleur::Noir
+        {
+            log::warn!("Niveaux de gris prévus pour les écrans noir et blanc, pas pour {}", M::NOM);
+        }
+        self.table_forme = table_forme;
+        if self.etat_alimentation == EtatAlimentation::Actif {
+            self.etat_alimentation = EtatAlimentation::Eteint;
+        }
+    }
+
+    /// Transmission des plans selon le modèle et la table de formes d'onde
+    pub(crate) fn transmission(&self) -> &'static [TransmissionPlan] {
+        match &self.table_forme {
+            Some(table_forme) if table_forme.est_noir_blanc() => TRANSMISSION_NOIR_BLANC,
+            Some(table_forme) if table_forme.est_gris() => TRANSMISSION_GRIS_4,
+            _ => M::TRANSMISSION,
+        }
+    }
+
+    /// Charger la table de formes d'onde dans les registres du contrôleur, ou choisir les formes
+    /// d'onde en niveaux de gris de sa mémoire OTP
+    fn charger_table_forme(&mut self, table_forme: &TableForme) -> Result<(), ErreurEcran> {
+        let reglage_panneau = M::INITIALISATION.iter().find_map(|etape| match etape {
+            Etape::Instruction(Instruction::PanelSetting, [reglage, ..]) => Some(*reglage),
+            _ => None,
+        });
+        match reglage_panneau {
+            Some(reglage_panneau) => {
+                // Bit 5 : tables lues dans les registres, bit 4 : mode noir et blanc, dans lequel
+                // les bits des deux plans choisissent la forme d'onde de chaque pixel
+                let mut reglage_panneau = reglage_panneau;
+                if !table_forme.est_gris() {
+                    reglage_panneau |= 0x20;
+                }
+                if table_forme.est_noir_blanc() || table_forme.est_gris() {
+                    reglage_panneau |= 0x10;
+                }
+                self.envoyer_instruction(Instruction::PanelSetting, &[reglage_panneau])?;
+            }
+            None => log::warn!("Réglage du panneau absent de l'initialisation de {}", M::NOM),
+        }
+
+        if table_forme.est_gris() {
+            self.envoyer_instruction(Instruction::BoosterSoftStart, &GRIS_DEMARRAGE_CONVERTISSEUR)?;
+            self.envoyer_instruction(Instruction::CascadeSetting, &[GRIS_CASCADE])?;
+            self.envoyer_instruction(Instruction::ForceTemperature, &[GRIS_TEMPERATURE])?;
+            return Ok(());
+        }
+        self.envoyer_instruction(Instruction::LutVcom, table_forme.vcom())?;
+        self.envoyer_instruction(Instruction::LutWhiteWhite, table_forme.ww())?;
+        self.envoyer_instruction(Instruction::LutBlackWhite, table_forme.bw())?;
+        self.envoyer_instruction(Instruction::LutWhiteBlack, table_forme.wb())?;
+        self.envoyer_instruction(Instruction::LutBlackBlack, table_forme.bb())?;
+        Ok(())
+    }
+
+    /// Dernière température mesurée par [`Self::lire_temperature`] en °C
+    pub fn derniere_temperature(&self) -> Option<f32> {
+        self.derniere_temperature
+    }
+
+    /// Contrôle de la température avant un rafraîchissement
+    pub fn controle_temperature(&self) -> ControleTemperature {
+        self.controle_temperature
+    }
+
+    /// Définir le contrôle de la dernière température mesurée avant un rafraîchissement
+    pub fn definir_controle_temperature(&mut self, controle_temperature: ControleTemperature) {
+        self.controle_temperature = controle_temperature;
+    }
+
+    /// Vérifier que la dernière température mesurée est dans la plage de fonctionnement du
+    /// modèle
+    fn verifier_temperature(&self) -> Result<(), ErreurEcran> {
+        let Some(temperature) = self.derniere_temperature else {
+            return Ok(());
+        };
+        if (M::TEMPERATURE_MINIMALE..=M::TEMPERATURE_MAXIMALE).contains(&temperature) {
+            return Ok(());
+        }
+
+        match self.controle_temperature {
+            ControleTemperature::Ignorer => Ok(()),
+            ControleTemperature::Avertir => {
+                log::warn!("Température de l'écran hors de la plage de fonctionnement : {temperature} °C");
+                Ok(())
+            }
+            ControleTemperature::Refuser => Err(ErreurEcran::TemperatureHorsPlage { temperature }),
+        }
+    }
+
+    /// Etat d'alimentation du contrôleur de l'écran
+    pub fn etat_alimentation(&self) -> EtatAlimentation {
+        self.etat_alimentation
+    }
+
+    /// Indiquer si l'écran reste sous tension entre deux mises à jour
+    pub fn maintien_actif(&self) -> bool {
+        self.maintien_actif
+    }
+
+    /// Garder l'écran sous tension entre deux mises à jour au lieu de le mettre en veille
+    /// profonde après chaque rafraîchissement. Les mises à jour successives évitent alors la
+    /// réinitialisation matérielle et l'initialisation.
+    pub fn definir_maintien_actif(&mut self, maintien_actif: bool) {
+        self.maintien_actif = maintien_actif;
+    }
+
+    /// Délai maximal d'attente de la disponibilité du contrôleur
+    pub fn delai_occupation(&self) -> Duration {
+        self.delai_occupation
+    }
+
+    /// Définir le délai maximal d'attente de la disponibilité du contrôleur.
+    /// Au-delà, les méthodes renvoient [`ErreurEcran::DelaiDepasse`].
+    pub fn definir_delai_occupation(&mut self, delai: Duration) {
+        self.delai_occupation = delai;
+    }
 
-        let spi = Spi::new(spi_bus, SlaveSelect::Ss0, 4000000, Mode::Mode0)?;
-        let gpio = Gpio::new().unwrap();
-        let rst = gpio.get(rst_numero_pin).unwrap().into_output();
-        let dc = gpio.get(dc_numero_pin).unwrap().into_output();
-        let cs = gpio.get(cs_numero_pin).unwrap().into_output();
-        let busy = gpio.get(busy_numero_pin).unwrap().into_input();
+    /// Largeur de l'écran selon la rotation (largeur physique : [`Ecran::LARGEUR`])
+    pub fn largeur(&self) -> usize {
+        self.memoire_tampon.largeur()
+    }
+
+    /// Hauteur de l'écran selon la rotation (hauteur physique : [`Ecran::HAUTEUR`])
+    pub fn hauteur(&self) -> usize {
+        self.memoire_tampon.hauteur()
+    }
+
+    /// Rotation de l'image
+    pub fn rotation(&self) -> Rotation {
+        self.memoire_tampon.rotation()
+    }
+
+    /// Définir la rotation de l'image. Les pixels sont ensuite adressés en coordonnées logiques
+    /// et placés dans les plans selon l'orientation physique de l'écran.
+    /// Le contenu de la mémoire tampon n'est pas modifié.
+    pub fn definir_rotation(&mut self, rotation: Rotation) {
+        self.memoire_tampon.definir_rotation(rotation);
+    }
+
+    /// Indiquer si l'image est retournée horizontalement
+    pub fn miroir(&self) -> bool {
+        self.memoire_tampon.miroir()
+    }
 
-       Ok( Self {
-            spi: spi,
-            dc: dc,
-            rst: rst,
-            cs: cs,
-            busy: busy,
-            buffer_red: [0; BUFFER_SIZE],
-            buffer_black: [0; BUFFER_SIZE],
-        })
+    /// Retourner horizontalement l'image, avant la rotation
+    /// Le contenu de la mémoire tampon n'est pas modifié.
+    pub fn definir_miroir(&mut self, miroir: bool) {
+        self.memoire_tampon.definir_miroir(miroir);
     }
 
-    /// Largeur de l'écran
-    pub const fn largeur() -> usize {
-        DISPLAY_WIDTH
+    /// Mémoire tampon du programme
+    pub fn memoire_tampon(&self) -> &MemoireTampon {
+        &self.memoire_tampon
     }
 
-    /// Hauteur de l'écran
-    pub const fn hauteur() -> usize {
-        DISPLAY_HEIGHT
+    /// Mémoire tampon du programme modifiable
+    /// Le contenu n'est pas transféré à l'écran
+    pub fn memoire_tampon_mut(&mut self) -> &mut MemoireTampon {
+        &mut self.memoire_tampon
+    }
+
+    /// Remplacer le plan noir de la mémoire tampon du programme (1 bit par pixel, 0 pour un
+    /// pixel noir) selon les dimensions physiques de l'écran
+    /// L'image n'est pas transférée à l'écran
+    pub fn definir_plan_noir(&mut self, plan: &[u8]) -> Result<(), ErreurEcran> {
+        self.memoire_tampon.definir_plan_noir(plan)
+    }
+
+    /// Remplacer le plan rouge de la mémoire tampon du programme (1 bit par pixel, 1 pour un
+    /// pixel rouge) selon les dimensions physiques de l'écran
+    /// Le plan rouge est ignoré par les écrans noir et blanc.
+    /// L'image n'est pas transférée à l'écran
+    pub fn definir_plan_rouge(&mut self, plan: &[u8]) -> Result<(), ErreurEcran> {
+        self.memoire_tampon.definir_plan_rouge(plan)
+    }
+
+    /// Modifier la couleur d'un pixel de la mémoire tampon du programme, en coordonnées
+    /// logiques
+    /// L'image n'est pas transférée à l'écran
+    pub fn definir_pixel(&mut self, x: usize, y: usize, couleur: TriCouleur) -> Result<(), ErreurEcran> {
+        if x >= self.largeur() || y >= self.hauteur() {
+            return Err(ErreurEcran::ZoneInvalide(Zone::new(x, y, 1, 1)));
+        }
+        self.memoire_tampon.definir_pixel(x, y, couleur);
+        Ok(())
+    }
+
+    /// Couleur d'un pixel de la mémoire tampon du programme, en coordonnées logiques
+    pub fn pixel(&self, x: usize, y: usize) -> Option<TriCouleur> {
+        self.memoire_tampon.pixel(x, y)
     }
 
     /// Réinitialiser l'écran
-    pub(crate) async fn reinitialiser(&mut self) -> Result<(), rppal::gpio::Error> {
-        self.rst.set_high();
+    pub(crate) async fn reinitialiser(&mut self) {
+        self.rst.mettre_niveau_haut();
         sleep(Duration::from_millis(200)).await;
-        self.rst.set_low();
+        self.rst.mettre_niveau_bas();
         sleep(Duration::from_millis(4)).await;
-        self.rst.set_high();
+        self.rst.mettre_niveau_haut();
         sleep(Duration::from_millis(200)).await;
+        self.etat_alimentation = EtatAlimentation::Eteint;
+    }
 
+    /// Vérifier que le contrôleur peut recevoir des commandes
+    fn verifier_eveil(&self) -> Result<(), ErreurEcran> {
+        match self.etat_alimentation {
+            EtatAlimentation::Veille => Err(ErreurEcran::VeilleProfonde),
+            _ => Ok(()),
+        }
+    }
+
+    /// Initialiser l'écran s'il n'est pas déjà initialisé et sous tension
+    async fn activer(&mut self) -> Result<(), ErreurEcran> {
+        if self.etat_alimentation != EtatAlimentation::Actif {
+            log::debug!("Réinitialisation automatique ({:?})", self.etat_alimentation);
+            self.initialiser().await?;
+        }
         Ok(())
     }
 
@@ -74,13 +454,14 @@ impl Wepd7In5BV2 {
         &mut self,
         commande: Instruction,
         data: &[u8],
-    ) -> Result<(), Error> {
-        self.dc.set_low();
-        self.cs.set_low();
-        self.spi.write(&[commande as u8])?;
-        self.cs.set_high();
+    ) -> Result<(), ErreurEcran> {
+        self.verifier_eveil()?;
+        self.dc.mettre_niveau_bas();
+        self.cs.mettre_niveau_bas();
+        self.spi.ecrire(&[commande as u8]).map_err(ErreurEcran::spi)?;
+        self.cs.mettre_niveau_haut();
 
-        if data.len() > 0 {
+        if !data.is_empty() {
             self.envoyer_donnees(data)?;
         }
 
@@ -88,9 +469,11 @@ impl Wepd7In5BV2 {
     }
 
     /// Envoyer les données de la mémoire tampon 
-    pub fn envoyer_donnees(&mut self, data: &[u8]) -> Result<(), Error> {
-        self.dc.set_high();
-        self.cs.set_low();
+    /// Renvoie [`ErreurEcran::VeilleProfonde`] si le contrôleur est en veille profonde
+    pub fn envoyer_donnees(&mut self, data: &[u8]) -> Result<(), ErreurEcran> {
+        self.verifier_eveil()?;
+        self.dc.mettre_niveau_haut();
+        self.cs.mettre_niveau_bas();
 
         let date_len = data.len();
         let mut idx_pixels_sent = 0;
@@ -100,52 +483,143 @@ impl Wepd7In5BV2 {
             let number_available_pixels_to_send = date_len - idx_pixels_sent;
             let number_pixels_to_send = cmp::min(number_available_pixels_to_send, multiplier);
             self.spi
-                .write(&data[idx_pixels_sent..idx_pixels_sent + number_pixels_to_send])?;
-            idx_pixels_sent = idx_pixels_sent + number_pixels_to_send;
+                .ecrire(&data[idx_pixels_sent..idx_pixels_sent + number_pixels_to_send])
+                .map_err(ErreurEcran::spi)?;
+            idx_pixels_sent += number_pixels_to_send;
         }
-        self.cs.set_high();
+        self.cs.mettre_niveau_haut();
         Ok(())
     }
 
+    /// Lire la broche BUSY selon la polarité du modèle
+    fn lire_occupation(&self) -> bool {
+        self.busy.est_niveau_bas() == M::OCCUPE_NIVEAU_BAS
+    }
+
     /// Attendre que le contrôleur de l'écran soit disponible
-    pub async fn est_occupe(&mut self) -> Result<(), Error> {
-        let mut i = 0;
-        self.envoyer_instruction(Instruction::BusyStatus, &[])?;
-        let mut busy = self.busy.is_low();
-        while busy && i < 1000 {
-            sleep(Duration::from_millis(100)).await;
+    pub async fn est_occupe(&mut self) -> Result<(), ErreurEcran> {
+        self.attendre_disponibilite(EtapeEcran::Attente).await
+    }
+
+    /// Recevoir les fronts de la broche BUSY si elle prend en charge les interruptions
+    fn surveiller_occupation(&mut self) -> Option<Receiver<()>> {
+        if let SurveillanceOccupation::NonDemarree = self.surveillance_occupation {
+            let (tx, rx) = flume::unbounded();
+            self.surveillance_occupation = if self.busy.surveiller_fronts(Box::new(move || {
+                let _ = tx.send(());
+            })) {
+                log::debug!("Attente de la disponibilité par interruption");
+                SurveillanceOccupation::Interruption(rx)
+            } else {
+                log::debug!("Attente de la disponibilité par scrutation");
+                SurveillanceOccupation::Scrutation
+            };
+        }
+
+        match &self.surveillance_occupation {
+            SurveillanceOccupation::Interruption(rx) => Some(rx.clone()),
+            _ => None,
+        }
+    }
+
+    /// Attendre que le contrôleur de l'écran soit disponible pendant une étape, au plus pendant
+    /// le délai maximal d'attente.
+    /// Les fronts de la broche BUSY sont attendus par interruption lorsque la broche le permet,
+    /// sinon la broche est lue toutes les 100 ms.
+    async fn attendre_disponibilite(&mut self, etape: EtapeEcran) -> Result<(), ErreurEcran> {
+        let debut = Instant::now();
+        let fronts = self.surveiller_occupation();
+        if let Some(fronts) = &fronts {
+            fronts.drain();
+        }
+
+        // L'état renvoyé par BusyStatus remplacerait la réponse attendue du capteur de température
+        let interroger = etape != EtapeEcran::MesureTemperature;
+        if interroger {
             self.envoyer_instruction(Instruction::BusyStatus, &[])?;
-            busy = self.busy.is_low();
-            i += 1;
         }
-        if i ==1000{
-            log::error!("Attente maximale atteinte")
+        while self.lire_occupation() {
+            let duree_ecoulee = debut.elapsed();
+            if duree_ecoulee >= self.delai_occupation {
+                log::error!("Attente maximale atteinte ({etape})");
+                if let Some(attentes) = &mut self.attentes_mesurees {
+                    attentes.push((etape, duree_ecoulee));
+                }
+                return Err(ErreurEcran::DelaiDepasse { etape });
+            }
+            match &fronts {
+                Some(fronts) => {
+                    let attente = cmp::min(
+                        self.delai_occupation - duree_ecoulee,
+                        INTERVALLE_OCCUPATION_INTERRUPTION,
+                    );
+                    let _ = timeout(attente, fronts.recv_async()).await;
+                    fronts.drain();
+                }
+                None => sleep(INTERVALLE_OCCUPATION).await,
+            }
+            if interroger {
+                self.envoyer_instruction(Instruction::BusyStatus, &[])?;
+            }
+        }
+        if let Some(attentes) = &mut self.attentes_mesurees {
+            attentes.push((etape, debut.elapsed()));
         }
         Ok(())
     }
 
+    /// Commencer à enregistrer la durée de chaque attente de la disponibilité du contrôleur,
+    /// y compris celle interrompue par le délai maximal
+    pub(crate) fn demarrer_mesure_attentes(&mut self) {
+        self.attentes_mesurees = Some(Vec::new());
+    }
+
+    /// Arrêter l'enregistrement et renvoyer les durées enregistrées depuis son démarrage
+    pub(crate) fn terminer_mesure_attentes(&mut self) -> Vec<(EtapeEcran, Duration)> {
+        self.attentes_mesurees.take().unwrap_or_default()
+    }
+
     /// Initialiser l'écran
-    pub async fn initialiser(&mut self) -> Result<(), Error> {
+    pub async fn initialiser(&mut self) -> Result<(), ErreurEcran> {
         log::debug!("Initialisation");
-        self.reinitialiser().await.unwrap();
-
-        self.envoyer_instruction(Instruction::PowerSetting, &[0x07, 0x07, 0x3f, 0x3f])?;
-        self.envoyer_instruction(Instruction::PowerOn, &[0xF7])?;
-        sleep(Duration::from_millis(100)).await;
-        self.est_occupe().await?;
-        self.envoyer_instruction(Instruction::PanelSetting, &[0x0F])?;
-        self.envoyer_instruction(Instruction::Tres, &[0x03, 0x20, 0x01, 0xE0])?;
-        self.envoyer_instruction(Instruction::Unknown1, &[0x00])?;
-        self.envoyer_instruction(Instruction::VcomAndDataIntervalSetting, &[0x11, 0x07])?;
-        self.envoyer_instruction(Instruction::TconSetting, &[0x22])?;
-        self.envoyer_instruction(Instruction::Unknown2, &[0x00, 0x00, 0x00, 0x00])?;
+        self.reinitialiser().await;
+
+        self.executer(M::INITIALISATION, EtapeEcran::Initialisation).await?;
+        if let Some(table_forme) = self.table_forme.clone() {
+            self.charger_table_forme(&table_forme)?;
+        }
+        self.etat_alimentation = EtatAlimentation::Actif;
         log::debug!("Initialisation terminée");
         Ok(())
     }
 
     /// Convertir une image RGB565 et la sauvegarder dans la mémoire tampon du programme
+    /// Les pixels rouges sont blancs sur un écran noir et blanc.
     /// L'image n'est pas transfée à l'écran
-    pub fn sauvegarder_image_memoire_tampon(&mut self, image: &[u8]) -> Result<(), Error> {
+    pub fn sauvegarder_image_memoire_tampon(&mut self, image: &[u8]) -> Result<(), ErreurEcran> {
+        let taille_attendue = M::LARGEUR * M::HAUTEUR * 2;
+        if image.len() != taille_attendue {
+            return Err(ErreurEcran::TailleImageInvalide {
+                attendue: taille_attendue,
+                recue: image.len(),
+            });
+        }
+
+        let rouge = M::PLANS_COULEUR == PlansCouleur::NoirRouge;
+
+        // Image en coordonnées logiques lorsque l'écran est tourné ou retourné
+        if !self.memoire_tampon.est_orientation_physique() {
+            let largeur = self.largeur();
+            for (i, pixel) in image.chunks_exact(2).enumerate() {
+                let couleur = match pixel {
+                    [0, 0] => TriCouleur::Noir,
+                    [0, _] if rouge => TriCouleur::Rouge,
+                    _ => TriCouleur::Blanc,
+                };
+                self.memoire_tampon.definir_pixel(i % largeur, i / largeur, couleur);
+            }
+            return Ok(());
+        }
 
         for i in (0..image.len()).step_by(16) {
             let mut couleur_8pixels_noir: u8 = 0xFF;
@@ -153,55 +627,743 @@ impl Wepd7In5BV2 {
             for j in 0..8 {
                 if image[i + j * 2] == 0 && image[i + j * 2 + 1] == 0 {
                     couleur_8pixels_noir &= !(0x80 >> (j % 8));
-                } else if image[i + j * 2] == 0 && image[i + j * 2 + 1] != 0 {
+                } else if rouge && image[i + j * 2] == 0 && image[i + j * 2 + 1] != 0 {
                     couleur_8pixels_rouge &= !(0x80 >> (j % 8));
                 }
             }
-            self.buffer_black[i / 8 / 2] = couleur_8pixels_noir;
-            self.buffer_red[i / 8 / 2] = !couleur_8pixels_rouge;
+            self.memoire_tampon.plan_noir_mut()[i / 8 / 2] = couleur_8pixels_noir;
+            self.memoire_tampon.plan_rouge_mut()[i / 8 / 2] = !couleur_8pixels_rouge;
         }
 
         Ok(())
     }
 
-    /// Attendre que le contrôleur de l'écran soit disponible
-    pub async fn eteindre(&mut self) -> Result<(), Error> {
-        log::debug!("Extinction");
-        self.envoyer_instruction(Instruction::PowerOff, &[])?;
-        self.est_occupe().await?;
+    /// Exécuter une séquence de commandes du modèle
+    async fn executer(&mut self, etapes: &[Etape], etape_ecran: EtapeEcran) -> Result<(), ErreurEcran> {
+        for etape in etapes {
+            match etape {
+                Etape::Instruction(instruction, donnees) => {
+                    self.envoyer_instruction(*instruction, donnees)?
+                }
+                Etape::Attente(duree) => sleep(Duration::from_millis(*duree)).await,
+                Etape::AttenteDisponibilite => self.attendre_disponibilite(etape_ecran).await?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Transmettre les plans noir et rouge au contrôleur selon le modèle
+    fn transmettre_plans(&mut self, plan_noir: &[u8], plan_rouge: &[u8]) -> Result<(), ErreurEcran> {
+        for transmission in self.transmission() {
+            let plan = match transmission.plan {
+                Plan::Noir => plan_noir,
+                Plan::Rouge => plan_rouge,
+            };
+            self.envoyer_instruction(transmission.instruction, &[])?;
+            if transmission.inverse || transmission.ou_exclusif_noir {
+                let plan: Vec<u8> = plan
+                    .iter()
+                    .zip(plan_noir)
+                    .map(|(octet, noir)| match transmission.ou_exclusif_noir {
+                        true => octet ^ noir,
+                        false => *octet,
+                    })
+                    .map(|octet| if transmission.inverse { !octet } else { octet })
+                    .collect();
+                self.envoyer_donnees(&plan)?;
+            } else {
+                self.envoyer_donnees(plan)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Remplacer la mémoire tampon du programme, par exemple par le résultat d'une [`Conversion`]
+    /// Les dimensions logiques doivent être celles de l'écran selon sa rotation. L'image est
+    /// copiée selon l'orientation de l'écran si la mémoire tampon a une autre orientation.
+    /// L'image n'est pas transférée à l'écran
+    pub fn charger_memoire_tampon(
+        &mut self,
+        memoire_tampon: MemoireTampon,
+    ) -> Result<(), ErreurEcran> {
+        let attendues = (self.largeur(), self.hauteur());
+        let recues = (memoire_tampon.largeur(), memoire_tampon.hauteur());
+        if recues != attendues {
+            return Err(ErreurEcran::DimensionsInvalides { attendues, recues });
+        }
 
-        self.envoyer_instruction(Instruction::DeepSleep, &[0xA5])?;
+        if memoire_tampon.rotation() == self.rotation() && memoire_tampon.miroir() == self.miroir()
+        {
+            self.memoire_tampon = memoire_tampon;
+        } else {
+            self.memoire_tampon.copier(&memoire_tampon);
+        }
+        Ok(())
+    }
+
+    /// Conversion adaptée aux plans de couleur du modèle
+    pub fn adapter_conversion(conversion: &Conversion) -> Conversion {
+        let mut conversion = *conversion;
+        if M::PLANS_COULEUR == PlansCouleur::Noir {
+            conversion.palette.rouge = None;
+        }
+        conversion
+    }
+
+    /// Convertir une image RGB (3 octets par pixel) aux dimensions de l'écran et la sauvegarder
+    /// dans la mémoire tampon du programme
+    /// L'image n'est pas transférée à l'écran
+    pub fn sauvegarder_image_rgb(
+        &mut self,
+        pixels: &[u8],
+        conversion: &Conversion,
+    ) -> Result<(), ErreurEcran> {
+        let memoire_tampon = Self::adapter_conversion(conversion).convertir_rgb(
+            self.largeur(),
+            self.hauteur(),
+            pixels,
+        )?;
+        self.charger_memoire_tampon(memoire_tampon)
+    }
+
+    /// Convertir des luminances sur 8 bits (1 octet par pixel) aux dimensions de l'écran en 4
+    /// niveaux de gris et les sauvegarder dans la mémoire tampon du programme
+    /// Les niveaux de gris sont affichés avec le réglage [`TableForme::gris_4_niveaux`].
+    /// L'image n'est pas transférée à l'écran
+    pub fn sauvegarder_image_luminance(
+        &mut self,
+        pixels: &[u8],
+        conversion: &Conversion,
+    ) -> Result<(), ErreurEcran> {
+        let memoire_tampon =
+            conversion.convertir_luminance(self.largeur(), self.hauteur(), pixels)?;
+        self.charger_memoire_tampon(memoire_tampon)
+    }
+
+    /// Convertir une image aux dimensions de l'écran et la sauvegarder dans la mémoire tampon
+    /// du programme
+    /// L'image n'est pas transférée à l'écran
+    #[cfg(feature = "image")]
+    pub fn sauvegarder_image(
+        &mut self,
+        image: &image::RgbImage,
+        conversion: &Conversion,
+    ) -> Result<(), ErreurEcran> {
+        self.charger_memoire_tampon(Self::adapter_conversion(conversion).convertir_image(image))
+    }
+
+    /// Ajuster une image aux dimensions de l'écran, la convertir et la sauvegarder dans la
+    /// mémoire tampon du programme
+    /// L'image n'est pas transférée à l'écran
+    #[cfg(feature = "image")]
+    pub fn sauvegarder_image_ajustee(
+        &mut self,
+        image: &image::DynamicImage,
+        ajustement: Ajustement,
+        conversion: &Conversion,
+    ) -> Result<(), ErreurEcran> {
+        let memoire_tampon = Self::adapter_conversion(conversion).convertir_image_ajustee(
+            image,
+            self.largeur(),
+            self.hauteur(),
+            ajustement,
+        );
+        self.charger_memoire_tampon(memoire_tampon)
+    }
+
+    /// Lire un fichier image (PNG, BMP ou PBM/PGM/PPM), l'ajuster aux dimensions de l'écran, la
+    /// convertir et la sauvegarder dans la mémoire tampon du programme
+    /// L'image n'est pas transférée à l'écran
+    #[cfg(feature = "image")]
+    pub fn sauvegarder_fichier_image(
+        &mut self,
+        chemin: impl AsRef<Path>,
+        ajustement: Ajustement,
+        conversion: &Conversion,
+    ) -> Result<(), ErreurEcran> {
+        self.sauvegarder_image_ajustee(&lire_image(chemin)?, ajustement, conversion)
+    }
+
+    /// Dessiner un document SVG aux dimensions de l'écran et le sauvegarder dans la mémoire
+    /// tampon du programme, sur un fond blanc
+    /// Les pixels rouges sont convertis selon leur luminance sur un écran noir et blanc.
+    /// L'image n'est pas transférée à l'écran
+    #[cfg(feature = "svg")]
+    pub fn sauvegarder_svg(&mut self, image: &ImageSvg) -> Result<(), ErreurEcran> {
+        let mut seuils = image.seuils();
+        if M::PLANS_COULEUR == PlansCouleur::Noir {
+            seuils.rouge = None;
+        }
+        let mut memoire_tampon = MemoireTampon::new(self.largeur(), self.hauteur());
+        image.dessiner_zone(
+            &mut memoire_tampon,
+            Zone::new(0, 0, self.largeur(), self.hauteur()),
+            &seuils,
+        );
+        self.charger_memoire_tampon(memoire_tampon)
+    }
+
+    /// Ajuster une image aux dimensions de l'écran, la convertir en 4 niveaux de gris et la
+    /// sauvegarder dans la mémoire tampon du programme
+    /// Les niveaux de gris sont affichés avec le réglage [`TableForme::gris_4_niveaux`].
+    /// L'image n'est pas transférée à l'écran
+    #[cfg(feature = "image")]
+    pub fn sauvegarder_image_gris(
+        &mut self,
+        image: &image::DynamicImage,
+        ajustement: Ajustement,
+        conversion: &Conversion,
+    ) -> Result<(), ErreurEcran> {
+        let memoire_tampon = conversion.convertir_image_ajustee_gris(
+            image,
+            self.largeur(),
+            self.hauteur(),
+            ajustement,
+        );
+        self.charger_memoire_tampon(memoire_tampon)
+    }
+
+    /// Mettre l'écran hors tension et en veille profonde
+    /// Aucune commande n'est envoyée si l'écran est déjà en veille profonde.
+    pub async fn eteindre(&mut self) -> Result<(), ErreurEcran> {
+        if self.etat_alimentation == EtatAlimentation::Veille {
+            log::debug!("Ecran déjà en veille profonde");
+            return Ok(());
+        }
+
+        log::debug!("Extinction");
+        // L'état du contrôleur reste inconnu si l'extinction échoue
+        self.etat_alimentation = EtatAlimentation::Eteint;
+        self.executer(M::EXTINCTION, EtapeEcran::Extinction).await?;
+        self.etat_alimentation = EtatAlimentation::Veille;
         log::debug!("Extinction terminée");
         Ok(())
     }
 
     /// Mettre à jour l'écran en transférant le contenu de la mémoire tampon vers le contrôleur de l'écran
-    pub async fn mettre_a_jour(&mut self) -> Result<(), Error> {
+    /// L'écran n'est pas rafraîchi si la mémoire tampon est identique à la dernière image
+    /// transmise ([`MiseAJour::Inchangee`]).
+    /// L'écran est initialisé s'il n'est pas sous tension, puis mis en veille profonde sauf si
+    /// le maintien sous tension est activé.
+    pub async fn mettre_a_jour(&mut self) -> Result<MiseAJour, ErreurEcran> {
+        if self.est_affichee(&Zone::new(0, 0, M::LARGEUR, M::HAUTEUR)) {
+            return self.terminer_sans_rafraichissement().await;
+        }
+        self.forcer_mise_a_jour().await?;
+        Ok(MiseAJour::Effectuee)
+    }
+
+    /// Mettre à jour l'écran même si la mémoire tampon est identique à la dernière image
+    /// transmise
+    pub async fn forcer_mise_a_jour(&mut self) -> Result<(), ErreurEcran> {
+        self.verifier_temperature()?;
+        self.activer().await?;
 
         log::debug!("Mise à jour");
-        let buffer_black = self.buffer_black;
-        let buffer_red = self.buffer_red;
-
-        self.envoyer_instruction(Instruction::DataStartTransmission1, &[])?;
-        self.envoyer_donnees(&buffer_black)?;
-        self.envoyer_instruction(Instruction::DataStartTransmission2, &[])?;
-        self.envoyer_donnees(&buffer_red)?;
-        self.envoyer_instruction(Instruction::DisplayRefresh, &[])?;
-        sleep(Duration::from_millis(100)).await;
-        self.est_occupe().await?;
-        self.eteindre().await?;
+        let buffer_black = self.memoire_tampon.plan_noir().to_vec();
+        let buffer_red = self.memoire_tampon.plan_rouge().to_vec();
+
+        // L'état du contrôleur et l'image affichée restent inconnus si le rafraîchissement échoue
+        self.etat_alimentation = EtatAlimentation::Eteint;
+        self.image_affichee = None;
+        self.transmettre_plans(&buffer_black, &buffer_red)?;
+        self.executer(M::RAFRAICHISSEMENT, EtapeEcran::Rafraichissement).await?;
+        self.etat_alimentation = EtatAlimentation::Actif;
+        self.image_affichee = Some(self.memoire_tampon.clone());
+        self.enregistrer_image_affichee();
+        if !self.maintien_actif {
+            self.eteindre().await?;
+        }
         log::debug!("Mise à jour terminée");
         Ok(())
     }
 
     /// Effacer la mémoire tampon du programme
-    pub fn effacer_memoire_tampon(&mut self) -> Result<(), Error> {
-        for i in 0..self.buffer_black.len() - 1 {
-            self.buffer_black[i] = 0xff;
+    pub fn effacer_memoire_tampon(&mut self) -> Result<(), ErreurEcran> {
+        self.memoire_tampon.effacer(TriCouleur::Blanc);
+        Ok(())
+    }
+
+    /// Mettre à jour une zone de l'écran en transférant uniquement la fenêtre correspondante
+    /// de la mémoire tampon vers le contrôleur de l'écran.
+    /// La zone est exprimée en coordonnées logiques et élargie horizontalement aux multiples de
+    /// 8 pixels de l'écran physique.
+    /// L'alimentation et les zones inchangées sont gérées comme pour [`Self::mettre_a_jour`].
+    /// Tout l'écran est rafraîchi si le modèle ne prend pas en charge le rafraîchissement
+    /// partiel ([`ModeleEcran::FENETRE_PARTIELLE`]).
+    pub async fn mettre_a_jour_zone(&mut self, zone: Zone) -> Result<MiseAJour, ErreurEcran> {
+        if zone.est_vide() || !zone.est_contenue(self.largeur(), self.hauteur()) {
+            return Err(ErreurEcran::ZoneInvalide(zone));
+        }
+        let zone_physique = self.memoire_tampon.zone_physique(&zone).aligner();
+        if self.est_affichee(&zone_physique) {
+            return self.terminer_sans_rafraichissement().await;
+        }
+        if !M::FENETRE_PARTIELLE {
+            log::debug!("Rafraîchissement partiel non pris en charge par {}", M::NOM);
+            self.forcer_mise_a_jour().await?;
+            return Ok(MiseAJour::Effectuee);
+        }
+        self.verifier_temperature()?;
+        self.activer().await?;
+
+        log::debug!("Mise à jour partielle {zone:?}");
+        self.transmettre_zone(zone_physique).await?;
+        log::debug!("Mise à jour partielle terminée");
+        Ok(MiseAJour::Effectuee)
+    }
+
+    /// Transmettre une zone alignée sur 8 pixels et rafraîchir la fenêtre correspondante
+    async fn transmettre_zone(&mut self, zone: Zone) -> Result<(), ErreurEcran> {
+        let (plan_noir, plan_rouge) = self.memoire_tampon.extraire_zone(&zone);
+        let x_fin = zone.x + zone.largeur - 1;
+        let y_fin = zone.y + zone.hauteur - 1;
+
+        // L'état du contrôleur et l'image affichée restent inconnus si le rafraîchissement échoue
+        self.etat_alimentation = EtatAlimentation::Eteint;
+        let mut image_affichee = self.image_affichee.take();
+        self.envoyer_instruction(Instruction::PartialIn, &[])?;
+        self.envoyer_instruction(
+            Instruction::PartialWindow,
+            &[
+                (zone.x >> 8) as u8,
+                (zone.x & 0xF8) as u8,
+                (x_fin >> 8) as u8,
+                (x_fin & 0xFF) as u8 | 0x07,
+                (zone.y >> 8) as u8,
+                (zone.y & 0xFF) as u8,
+                (y_fin >> 8) as u8,
+                (y_fin & 0xFF) as u8,
+                0x01,
+            ],
+        )?;
+        self.transmettre_plans(&plan_noir, &plan_rouge)?;
+        self.executer(M::RAFRAICHISSEMENT, EtapeEcran::Rafraichissement).await?;
+        self.envoyer_instruction(Instruction::PartialOut, &[])?;
+        self.etat_alimentation = EtatAlimentation::Actif;
+        if let Some(image_affichee) = image_affichee.as_mut() {
+            image_affichee.ecrire_zone(&zone, Plan::Noir, &plan_noir);
+            image_affichee.ecrire_zone(&zone, Plan::Rouge, &plan_rouge);
         }
-        for i in 0..self.buffer_red.len() - 1 {
-            self.buffer_red[i] = 0x00;
+        self.image_affichee = image_affichee;
+        self.enregistrer_image_affichee();
+        if !self.maintien_actif {
+            self.eteindre().await?;
         }
         Ok(())
     }
 }
+
+impl<M: ModeleEcran, S: LectureSpi, O: BrocheSortie, I: BrocheEntree> Ecran<M, S, O, I> {
+    /// Lire la température mesurée par le capteur du contrôleur en °C
+    /// L'écran est initialisé s'il n'est pas sous tension et le reste après la lecture.
+    /// Une réponse uniformément à 0xFF est refusée : elle provient d'une ligne MISO non câblée
+    /// ou maintenue au niveau haut. Une réponse à 0x00 est une mesure de 0 °C.
+    pub async fn lire_temperature(&mut self) -> Result<f32, ErreurEcran> {
+        self.activer().await?;
+        self.envoyer_instruction(Instruction::TemperatureSensorCalibration, &[])?;
+        self.attendre_disponibilite(EtapeEcran::MesureTemperature).await?;
+
+        let mut octets = [0; 2];
+        self.dc.mettre_niveau_haut();
+        self.cs.mettre_niveau_bas();
+        let lecture = self.spi.lire(&mut octets);
+        self.cs.mettre_niveau_haut();
+        lecture.map_err(ErreurEcran::spi)?;
+        if octets == [0xFF; 2] {
+            return Err(ErreurEcran::TemperatureIllisible { octets });
+        }
+
+        let temperature = temperature::decoder(octets);
+        log::debug!("Température de l'écran : {temperature} °C");
+        self.derniere_temperature = Some(temperature);
+        Ok(temperature)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecran::memoire_tampon::Gris4;
+    use crate::ecran::simulation::{Commande, ControleurSimule};
+
+    /// Données reçues après chaque occurrence d'une instruction
+    fn donnees(commandes: &[Commande], instruction: Instruction) -> Vec<Vec<u8>> {
+        commandes
+            .iter()
+            .filter(|commande| commande.instruction() == Some(instruction))
+            .map(|commande| commande.donnees.clone())
+            .collect()
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn fenetre_partielle() {
+        let controleur = ControleurSimule::new();
+        let mut ecran = controleur.ecran();
+        ecran.definir_maintien_actif(true);
+
+        // Abscisses élargies aux multiples de 8, fin de la fenêtre incluse
+        let mise_a_jour = ecran.mettre_a_jour_zone(Zone::new(300, 260, 20, 10)).await;
+        assert_eq!(mise_a_jour.unwrap(), MiseAJour::Effectuee);
+
+        let commandes = controleur.commandes();
+        assert_eq!(
+            donnees(&commandes, Instruction::PartialWindow),
+            vec![vec![0x01, 0x28, 0x01, 0x3F, 0x01, 0x04, 0x01, 0x0D, 0x01]]
+        );
+        assert_eq!(donnees(&commandes, Instruction::PartialIn).len(), 1);
+        assert_eq!(donnees(&commandes, Instruction::PartialOut).len(), 1);
+        assert_eq!(
+            donnees(&commandes, Instruction::DataStartTransmission1),
+            vec![vec![0xFF; 3 * 10]]
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn fenetre_partielle_hors_ecran() {
+        let controleur = ControleurSimule::new();
+        let mut ecran = controleur.ecran();
+
+        let zone = Zone::new(usize::MAX - 4, 0, 8, 8);
+        let mise_a_jour = ecran.mettre_a_jour_zone(zone).await;
+        assert!(matches!(mise_a_jour, Err(ErreurEcran::ZoneInvalide(z)) if z == zone));
+        assert!(controleur.commandes().is_empty());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn fenetre_partielle_non_prise_en_charge() {
+        let controleur = ControleurSimule::new();
+        let mut ecran = controleur.ecran_modele::<Modele4In2B>();
+
+        let mise_a_jour = ecran.mettre_a_jour_zone(Zone::new(0, 0, 8, 8)).await;
+        assert_eq!(mise_a_jour.unwrap(), MiseAJour::Effectuee);
+
+        let commandes = controleur.commandes();
+        assert!(donnees(&commandes, Instruction::PartialIn).is_empty());
+        assert!(donnees(&commandes, Instruction::PartialWindow).is_empty());
+        assert_eq!(
+            donnees(&commandes, Instruction::DataStartTransmission1),
+            vec![vec![0xFF; Modele4In2B::LARGEUR * Modele4In2B::HAUTEUR / 8]]
+        );
+        assert_eq!(ecran.image_affichee(), Some(ecran.memoire_tampon()));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn transmission_gris_4() {
+        let controleur = ControleurSimule::new();
+        let mut ecran = controleur.ecran_modele::<Modele7In5V2>();
+        ecran.definir_table_forme(Some(TableForme::gris_4_niveaux()));
+        for (x, gris) in [
+            Gris4::Blanc,
+            Gris4::GrisClair,
+            Gris4::GrisFonce,
+            Gris4::Noir,
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            ecran.memoire_tampon_mut().definir_gris(x, 0, gris);
+        }
+        ecran.forcer_mise_a_jour().await.unwrap();
+
+        // Bits (DTM1, DTM2) : blanc (0, 0), gris clair (0, 1), gris foncé (1, 0), noir (1, 1)
+        let commandes = controleur.commandes();
+        let dtm1 = donnees(&commandes, Instruction::DataStartTransmission1);
+        let dtm2 = donnees(&commandes, Instruction::DataStartTransmission2);
+        assert_eq!(dtm1[0][..2], [0b0011_0000, 0x00]);
+        assert_eq!(dtm2[0][..2], [0b0101_0000, 0x00]);
+        // Formes d'onde de la mémoire OTP choisies comme dans init_4Gray de epd7in5_V2.py
+        assert_eq!(
+            donnees(&commandes, Instruction::PanelSetting),
+            vec![vec![0x1F], vec![0x1F]]
+        );
+        assert_eq!(
+            donnees(&commandes, Instruction::BoosterSoftStart)[1],
+            [0x27, 0x27, 0x18, 0x17]
+        );
+        assert_eq!(
+            donnees(&commandes, Instruction::CascadeSetting),
+            vec![vec![0x02]]
+        );
+        assert_eq!(
+            donnees(&commandes, Instruction::ForceTemperature),
+            vec![vec![0x5F]]
+        );
+        assert!(donnees(&commandes, Instruction::LutVcom).is_empty());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn chargement_table_rapide() {
+        let controleur = ControleurSimule::new();
+        let mut ecran = controleur.ecran_modele::<Modele7In5V2>();
+        ecran.definir_table_forme(Some(TableForme::rapide_noir_blanc()));
+        ecran.forcer_mise_a_jour().await.unwrap();
+
+        // Réglage de l'initialisation, puis bits 5 (tables des registres) et 4 (noir et blanc)
+        let commandes = controleur.commandes();
+        assert_eq!(
+            donnees(&commandes, Instruction::PanelSetting),
+            vec![vec![0x1F], vec![0x3F]]
+        );
+        let table = TableForme::rapide_noir_blanc();
+        for (instruction, attendue) in [
+            (Instruction::LutVcom, table.vcom()),
+            (Instruction::LutWhiteWhite, table.ww()),
+            (Instruction::LutBlackWhite, table.bw()),
+            (Instruction::LutWhiteBlack, table.wb()),
+            (Instruction::LutBlackBlack, table.bb()),
+        ] {
+            assert_eq!(donnees(&commandes, instruction), vec![attendue.to_vec()]);
+        }
+
+        // Tables chargées dans l'ordre des registres, après le réglage du panneau et avant le
+        // rafraîchissement
+        let instructions = controleur.instructions();
+        let position = |cherchee| instructions.iter().rposition(|i| *i == cherchee).unwrap();
+        let debut = position(Instruction::LutVcom);
+        let registres: Vec<u8> = instructions[debut..debut + 5]
+            .iter()
+            .map(|instruction| *instruction as u8)
+            .collect();
+        assert_eq!(registres, [0x20, 0x21, 0x22, 0x23, 0x24]);
+        assert!(position(Instruction::PanelSetting) < debut);
+        assert!(debut + 5 < position(Instruction::DisplayRefresh));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn chargement_table_trois_couleurs() {
+        let controleur = ControleurSimule::new();
+        let mut ecran = controleur.ecran();
+        let table =
+            TableForme::new(vec![1; 6], vec![2; 6], vec![3; 12], vec![4; 6], vec![5; 18]).unwrap();
+        ecran.definir_table_forme(Some(table));
+        ecran.forcer_mise_a_jour().await.unwrap();
+
+        // Tables des registres sans le mode noir et blanc
+        let commandes = controleur.commandes();
+        assert_eq!(
+            donnees(&commandes, Instruction::PanelSetting),
+            vec![vec![0x0F], vec![0x2F]]
+        );
+        assert_eq!(donnees(&commandes, Instruction::LutVcom), vec![vec![1; 6]]);
+        assert_eq!(
+            donnees(&commandes, Instruction::LutBlackWhite),
+            vec![vec![3; 12]]
+        );
+        assert_eq!(
+            donnees(&commandes, Instruction::LutBlackBlack),
+            vec![vec![5; 18]]
+        );
+
+        // Sans table, le contrôleur est réinitialisé et reprend ses formes d'onde OTP
+        ecran.definir_table_forme(None);
+        controleur.effacer_journal();
+        ecran.forcer_mise_a_jour().await.unwrap();
+        let commandes = controleur.commandes();
+        assert_eq!(
+            donnees(&commandes, Instruction::PanelSetting),
+            vec![vec![0x0F]]
+        );
+        assert!(donnees(&commandes, Instruction::LutVcom).is_empty());
+    }
+
+    /// Instructions BusyStatus reçues par le contrôleur
+    fn interrogations(controleur: &ControleurSimule) -> usize {
+        controleur
+            .instructions()
+            .into_iter()
+            .filter(|instruction| *instruction == Instruction::BusyStatus)
+            .count()
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn attente_par_interruption() {
+        let controleur = ControleurSimule::new();
+        controleur.definir_interruptions(true);
+        controleur.definir_duree_occupation(usize::MAX);
+        let mut ecran = controleur.ecran();
+        ecran
+            .envoyer_instruction(Instruction::PowerOn, &[])
+            .unwrap();
+        controleur.effacer_journal();
+
+        let liberation = tokio::spawn({
+            let controleur = controleur.clone();
+            async move {
+                sleep(Duration::from_millis(250)).await;
+                controleur.liberer();
+            }
+        });
+        let debut = Instant::now();
+        ecran.est_occupe().await.unwrap();
+        liberation.await.unwrap();
+
+        // Fin de l'attente dès le front de la broche BUSY, sans attendre la lecture suivante
+        assert_eq!(debut.elapsed(), Duration::from_millis(250));
+        assert_eq!(interrogations(&controleur), 2);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn attente_par_interruption_bloquee() {
+        let controleur = ControleurSimule::new();
+        controleur.definir_interruptions(true);
+        controleur.definir_duree_occupation(usize::MAX);
+        let mut ecran = controleur.ecran();
+        ecran.definir_delai_occupation(Duration::from_secs(3));
+        ecran
+            .envoyer_instruction(Instruction::PowerOn, &[])
+            .unwrap();
+        controleur.effacer_journal();
+
+        let debut = Instant::now();
+        let attente = ecran.est_occupe().await;
+
+        // Sans front, la broche est relue chaque seconde jusqu'au délai maximal
+        assert!(matches!(
+            attente,
+            Err(ErreurEcran::DelaiDepasse {
+                etape: EtapeEcran::Attente
+            })
+        ));
+        assert_eq!(debut.elapsed(), Duration::from_secs(3));
+        assert_eq!(interrogations(&controleur), 4);
+        assert!(controleur.est_occupe());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn lecture_temperature_apres_mesure() {
+        let controleur = ControleurSimule::new();
+        let mut ecran = controleur.ecran();
+        ecran.initialiser().await.unwrap();
+        controleur.definir_temperature(-12.5);
+        controleur.effacer_journal();
+
+        assert_eq!(ecran.lire_temperature().await.unwrap(), -12.5);
+        assert_eq!(ecran.derniere_temperature(), Some(-12.5));
+        // La réponse est lue une fois le contrôleur disponible, sans interroger son état
+        assert_eq!(
+            controleur.instructions(),
+            vec![Instruction::TemperatureSensorCalibration]
+        );
+        assert!(!controleur.est_occupe());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn lecture_temperature_nulle() {
+        let controleur = ControleurSimule::new();
+        let mut ecran = controleur.ecran();
+        controleur.definir_temperature(0.0);
+
+        assert_eq!(ecran.lire_temperature().await.unwrap(), 0.0);
+        assert_eq!(ecran.derniere_temperature(), Some(0.0));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn lecture_temperature_sans_miso() {
+        let controleur = ControleurSimule::new();
+        let mut ecran = controleur.ecran();
+        controleur.definir_miso_cablee(false);
+
+        let lecture = ecran.lire_temperature().await;
+        assert!(matches!(
+            lecture,
+            Err(ErreurEcran::TemperatureIllisible {
+                octets: [0xFF, 0xFF]
+            })
+        ));
+        assert_eq!(ecran.derniere_temperature(), None);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn mise_a_jour_inchangee() {
+        let controleur = ControleurSimule::new();
+        let mut ecran = controleur.ecran();
+        ecran.definir_pixel(10, 10, TriCouleur::Noir).unwrap();
+        assert_eq!(ecran.mettre_a_jour().await.unwrap(), MiseAJour::Effectuee);
+        assert_eq!(
+            donnees(&controleur.commandes(), Instruction::DisplayRefresh).len(),
+            1
+        );
+
+        // Image identique : ni transmission ni rafraîchissement
+        controleur.effacer_journal();
+        assert_eq!(ecran.mettre_a_jour().await.unwrap(), MiseAJour::Inchangee);
+        let commandes = controleur.commandes();
+        assert!(donnees(&commandes, Instruction::DataStartTransmission1).is_empty());
+        assert!(donnees(&commandes, Instruction::DisplayRefresh).is_empty());
+
+        controleur.effacer_journal();
+        ecran.forcer_mise_a_jour().await.unwrap();
+        assert_eq!(
+            donnees(&controleur.commandes(), Instruction::DisplayRefresh).len(),
+            1
+        );
+
+        controleur.effacer_journal();
+        ecran.definir_pixel(10, 10, TriCouleur::Rouge).unwrap();
+        assert_eq!(ecran.mettre_a_jour().await.unwrap(), MiseAJour::Effectuee);
+        assert_eq!(
+            donnees(&controleur.commandes(), Instruction::DisplayRefresh).len(),
+            1
+        );
+    }
+
+    /// Image RGB565 avec un pixel noir en (0, 0) et un pixel rouge en (1, 0), en coordonnées
+    /// logiques
+    fn image_rgb565<M: ModeleEcran>(largeur: usize) -> Vec<u8> {
+        let mut image = vec![0xFF; M::LARGEUR * M::HAUTEUR * 2];
+        image[..2].copy_from_slice(&[0x00, 0x00]);
+        image[2..4].copy_from_slice(&[0x00, 0x1F]);
+        image[largeur * 2..largeur * 2 + 2].copy_from_slice(&[0x00, 0x00]);
+        image
+    }
+
+    #[test]
+    fn image_rgb565_rouge() {
+        for rotation in [Rotation::R0, Rotation::R90] {
+            let controleur = ControleurSimule::new();
+            let mut ecran = controleur.ecran();
+            ecran.definir_rotation(rotation);
+            let image = image_rgb565::<Modele7In5BV2>(ecran.largeur());
+            ecran.sauvegarder_image_memoire_tampon(&image).unwrap();
+            assert_eq!(ecran.pixel(0, 0), Some(TriCouleur::Noir), "{rotation:?}");
+            assert_eq!(ecran.pixel(1, 0), Some(TriCouleur::Rouge), "{rotation:?}");
+            assert_eq!(ecran.pixel(2, 0), Some(TriCouleur::Blanc), "{rotation:?}");
+            assert_eq!(ecran.pixel(0, 1), Some(TriCouleur::Noir), "{rotation:?}");
+        }
+    }
+
+    #[test]
+    fn image_rgb565_rouge_sur_ecran_noir_et_blanc() {
+        for rotation in [Rotation::R0, Rotation::R90] {
+            let controleur = ControleurSimule::new();
+            let mut ecran = controleur.ecran_modele::<Modele7In5V2>();
+            ecran.definir_rotation(rotation);
+            let image = image_rgb565::<Modele7In5V2>(ecran.largeur());
+            ecran.sauvegarder_image_memoire_tampon(&image).unwrap();
+            assert_eq!(ecran.pixel(0, 0), Some(TriCouleur::Noir), "{rotation:?}");
+            assert_eq!(ecran.pixel(1, 0), Some(TriCouleur::Blanc), "{rotation:?}");
+            assert_eq!(ecran.pixel(0, 1), Some(TriCouleur::Noir), "{rotation:?}");
+            assert!(ecran
+                .memoire_tampon()
+                .plan_rouge()
+                .iter()
+                .all(|octet| *octet == 0));
+        }
+    }
+
+    #[test]
+    fn dimensions_selon_rotation() {
+        let controleur = ControleurSimule::new();
+        let mut ecran = controleur.ecran();
+        assert_eq!((ecran.largeur(), ecran.hauteur()), (800, 480));
+
+        // Ecran en portrait : les dimensions logiques sont échangées
+        ecran.definir_rotation(Rotation::R90);
+        assert_eq!((ecran.largeur(), ecran.hauteur()), (480, 800));
+        assert_eq!((Wepd7In5BV2::LARGEUR, Wepd7In5BV2::HAUTEUR), (800, 480));
+        ecran.definir_pixel(479, 799, TriCouleur::Noir).unwrap();
+        assert_eq!(ecran.pixel(479, 799), Some(TriCouleur::Noir));
+        assert!(ecran.definir_pixel(480, 0, TriCouleur::Noir).is_err());
+    }
+}
@@ -0,0 +1,223 @@
+use std::{error::Error, fmt, io, path::PathBuf};
+
+use crate::ecran::zone::Zone;
+
+/// Erreurs renvoyées par l'écran
+#[derive(Debug)]
+pub enum ErreurEcran {
+    /// Erreur de la liaison SPI
+    Spi(Box<dyn Error + Send + Sync>),
+    /// Erreur d'accès aux broches GPIO
+    Gpio(rppal::gpio::Error),
+    /// Le contrôleur de l'écran est resté occupé au-delà du délai maximal
+    DelaiDepasse {
+        /// Etape pendant laquelle le contrôleur est resté occupé
+        etape: EtapeEcran,
+    },
+    /// Commande envoyée alors que le contrôleur est en veille profonde
+    VeilleProfonde,
+    /// Dernière température mesurée hors de la plage de fonctionnement de l'écran
+    TemperatureHorsPlage {
+        /// Température mesurée en °C
+        temperature: f32,
+    },
+    /// Réponse du capteur de température uniformément à 0xFF, typique d'une ligne MISO non câblée
+    TemperatureIllisible {
+        /// Octets lus
+        octets: [u8; 2],
+    },
+    /// Table de formes d'onde vide, trop longue ou incomplète
+    TableFormeInvalide {
+        /// Nombre d'octets de la table
+        taille: usize,
+    },
+    /// Zone vide ou dépassant les dimensions de l'écran
+    ZoneInvalide(Zone),
+    /// Nombre d'octets de l'image différent du nombre attendu
+    TailleImageInvalide {
+        /// Nombre d'octets attendus
+        attendue: usize,
+        /// Nombre d'octets reçus
+        recue: usize,
+    },
+    /// Dimensions de l'image différentes de celles de l'écran
+    DimensionsInvalides {
+        /// Dimensions attendues (largeur, hauteur)
+        attendues: (usize, usize),
+        /// Dimensions reçues (largeur, hauteur)
+        recues: (usize, usize),
+    },
+    /// Erreur de lecture ou d'écriture d'un fichier
+    Io(io::Error),
+    /// Erreur d'encodage ou de décodage d'une image
+    #[cfg(feature = "image")]
+    Image(image::ImageError),
+    /// Format de fichier non pris en charge
+    FormatNonPrisEnCharge(PathBuf),
+    /// Fichier de police de caractères invalide
+    #[cfg(feature = "rendu")]
+    PoliceInvalide(PathBuf),
+    /// Format de date invalide pour [`chrono::format::strftime`]
+    #[cfg(feature = "rendu")]
+    FormatDateInvalide(String),
+    /// Style de texte dont la taille ou l'interligne n'est pas un nombre positif
+    #[cfg(feature = "rendu")]
+    StyleTexteInvalide(String),
+    /// Mise en page illisible ou incohérente
+    #[cfg(feature = "mise_en_page")]
+    MiseEnPageInvalide(String),
+    /// Texte impossible à encoder en code QR ou en code-barres
+    #[cfg(feature = "code_barre")]
+    CodeBarreInvalide(String),
+    /// Document SVG illisible
+    #[cfg(feature = "svg")]
+    SvgInvalide(String),
+    /// Fichier d'image enregistrée illisible ou tronqué
+    ImageEnregistreeInvalide,
+    /// Image enregistrée pour un autre modèle d'écran
+    ModeleIncompatible {
+        /// Modèle de l'écran
+        attendu: &'static str,
+        /// Modèle de l'image enregistrée
+        recu: String,
+    },
+}
+
+/// Etapes du pilotage de l'écran pendant lesquelles le contrôleur peut rester occupé
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum EtapeEcran {
+    /// Initialisation
+    Initialisation,
+    /// Rafraîchissement de l'écran
+    Rafraichissement,
+    /// Mise hors tension et mise en veille profonde
+    Extinction,
+    /// Attente demandée par le programme
+    Attente,
+    /// Mesure de la température par le capteur du contrôleur
+    MesureTemperature,
+}
+
+impl fmt::Display for EtapeEcran {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EtapeEcran::Initialisation => write!(f, "initialisation"),
+            EtapeEcran::Rafraichissement => write!(f, "rafraîchissement"),
+            EtapeEcran::Extinction => write!(f, "extinction"),
+            EtapeEcran::Attente => write!(f, "attente"),
+            EtapeEcran::MesureTemperature => write!(f, "mesure de la température"),
+        }
+    }
+}
+
+impl ErreurEcran {
+    pub(crate) fn spi<E: Error + Send + Sync + 'static>(erreur: E) -> Self {
+        ErreurEcran::Spi(Box::new(erreur))
+    }
+}
+
+impl fmt::Display for ErreurEcran {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ErreurEcran::Spi(erreur) => write!(f, "Erreur de la liaison SPI : {erreur}"),
+            ErreurEcran::Gpio(erreur) => write!(f, "Erreur des broches GPIO : {erreur}"),
+            ErreurEcran::DelaiDepasse { etape } => write!(
+                f,
+                "Délai maximal dépassé, le contrôleur de l'écran est resté occupé ({etape})"
+            ),
+            ErreurEcran::VeilleProfonde => write!(
+                f,
+                "Le contrôleur de l'écran est en veille profonde, il doit être initialisé"
+            ),
+            ErreurEcran::TemperatureHorsPlage { temperature } => write!(
+                f,
+                "Température de l'écran hors de la plage de fonctionnement : {temperature} °C"
+            ),
+            ErreurEcran::TemperatureIllisible { octets } => write!(
+                f,
+                "Température de l'écran illisible : {octets:02X?} lus, la ligne MISO est-elle câblée ?"
+            ),
+            ErreurEcran::TableFormeInvalide { taille } => write!(
+                f,
+                "Table de formes d'onde invalide : {taille} octets, au plus 60 par groupes de 6"
+            ),
+            ErreurEcran::ZoneInvalide(zone) => write!(f, "Zone invalide : {zone:?}"),
+            ErreurEcran::TailleImageInvalide { attendue, recue } => write!(
+                f,
+                "Taille de l'image invalide : {recue} octets reçus, {attendue} attendus"
+            ),
+            ErreurEcran::DimensionsInvalides { attendues, recues } => write!(
+                f,
+                "Dimensions de l'image invalides : {}×{} reçues, {}×{} attendues",
+                recues.0, recues.1, attendues.0, attendues.1
+            ),
+            ErreurEcran::Io(erreur) => write!(f, "Erreur d'entrée/sortie : {erreur}"),
+            #[cfg(feature = "image")]
+            ErreurEcran::Image(erreur) => write!(f, "Erreur de l'image : {erreur}"),
+            ErreurEcran::FormatNonPrisEnCharge(chemin) => {
+                write!(f, "Format non pris en charge : {}", chemin.display())
+            }
+            #[cfg(feature = "rendu")]
+            ErreurEcran::PoliceInvalide(chemin) => {
+                write!(f, "Police de caractères invalide : {}", chemin.display())
+            }
+            #[cfg(feature = "rendu")]
+            ErreurEcran::FormatDateInvalide(format) => {
+                write!(f, "Format de date invalide : {format}")
+            }
+            #[cfg(feature = "rendu")]
+            ErreurEcran::StyleTexteInvalide(message) => {
+                write!(f, "Style de texte invalide : {message}")
+            }
+            #[cfg(feature = "mise_en_page")]
+            ErreurEcran::MiseEnPageInvalide(message) => {
+                write!(f, "Mise en page invalide : {message}")
+            }
+            #[cfg(feature = "code_barre")]
+            ErreurEcran::CodeBarreInvalide(message) => {
+                write!(f, "Code-barres impossible à encoder : {message}")
+            }
+            #[cfg(feature = "svg")]
+            ErreurEcran::SvgInvalide(message) => write!(f, "Document SVG invalide : {message}"),
+            ErreurEcran::ImageEnregistreeInvalide => {
+                write!(f, "Fichier d'image enregistrée invalide")
+            }
+            ErreurEcran::ModeleIncompatible { attendu, recu } => write!(
+                f,
+                "Image enregistrée pour le modèle {recu}, {attendu} attendu"
+            ),
+        }
+    }
+}
+
+impl Error for ErreurEcran {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ErreurEcran::Spi(erreur) => Some(erreur.as_ref()),
+            ErreurEcran::Gpio(erreur) => Some(erreur),
+            ErreurEcran::Io(erreur) => Some(erreur),
+            #[cfg(feature = "image")]
+            ErreurEcran::Image(erreur) => Some(erreur),
+            _ => None,
+        }
+    }
+}
+
+impl From<rppal::gpio::Error> for ErreurEcran {
+    fn from(erreur: rppal::gpio::Error) -> Self {
+        ErreurEcran::Gpio(erreur)
+    }
+}
+
+impl From<io::Error> for ErreurEcran {
+    fn from(erreur: io::Error) -> Self {
+        ErreurEcran::Io(erreur)
+    }
+}
+
+#[cfg(feature = "image")]
+impl From<image::ImageError> for ErreurEcran {
+    fn from(erreur: image::ImageError) -> Self {
+        ErreurEcran::Image(erreur)
+    }
+}
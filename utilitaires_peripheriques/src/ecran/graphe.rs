@@ -0,0 +1,548 @@
+use chrono::{DateTime, NaiveDateTime, TimeDelta, TimeZone};
+
+use crate::ecran::{
+    memoire_tampon::{MemoireTampon, TriCouleur},
+    texte::{Alignement, AlignementVertical, StyleTexte},
+    widget::{self, Contexte, Widget},
+    zone::Zone,
+};
+
+/// Longueur des tirets de la ligne de seuil en pixels
+const LONGUEUR_TIRET: i64 = 4;
+
+/// Forme du graphe
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum FormeGraphe {
+    /// Courbe reliant les mesures
+    #[default]
+    Courbe,
+    /// Barre verticale pour chaque mesure
+    Barres,
+}
+
+/// Seuil mis en évidence sur le graphe
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Seuil {
+    /// Valeur du seuil
+    pub valeur: f64,
+    /// Couleur des mesures dépassant le seuil et de la ligne de seuil
+    pub couleur: TriCouleur,
+    /// Mettre en évidence les mesures supérieures (`true`) ou inférieures au seuil
+    pub superieur: bool,
+}
+
+impl Seuil {
+    /// Mesures supérieures au seuil en rouge
+    pub fn superieur(valeur: f64) -> Self {
+        Self {
+            valeur,
+            couleur: TriCouleur::Rouge,
+            superieur: true,
+        }
+    }
+
+    /// Mesures inférieures au seuil en rouge
+    pub fn inferieur(valeur: f64) -> Self {
+        Self {
+            superieur: false,
+            ..Self::superieur(valeur)
+        }
+    }
+
+    fn est_depasse(&self, valeur: f64) -> bool {
+        match self.superieur {
+            true => valeur > self.valeur,
+            false => valeur < self.valeur,
+        }
+    }
+}
+
+/// Graphe d'une série de mesures datées
+///
+/// Les mesures sont dessinées en noir, celles dépassant le seuil dans la couleur du seuil.
+/// Les axes sont étiquetés avec les valeurs minimale et maximale et les dates de la première
+/// et de la dernière mesure.
+pub struct Graphe {
+    mesures: Vec<(NaiveDateTime, f64)>,
+    forme: FormeGraphe,
+    couleur: TriCouleur,
+    seuil: Option<Seuil>,
+    bande_min_max: Option<TimeDelta>,
+    axes: bool,
+    style_etiquettes: StyleTexte,
+    decimales: usize,
+    format_date: String,
+    minimum: Option<f64>,
+    maximum: Option<f64>,
+}
+
+impl Graphe {
+    /// Courbe noire avec axes. Les mesures sont triées par date.
+    pub fn new(mut mesures: Vec<(NaiveDateTime, f64)>) -> Self {
+        mesures.retain(|(_, valeur)| valeur.is_finite());
+        mesures.sort_by_key(|(date, _)| *date);
+        Self {
+            mesures,
+            forme: FormeGraphe::Courbe,
+            couleur: TriCouleur::Noir,
+            seuil: None,
+            bande_min_max: None,
+            axes: true,
+            style_etiquettes: StyleTexte::new(14.),
+            decimales: 0,
+            format_date: "%H:%M".to_string(),
+            minimum: None,
+            maximum: None,
+        }
+    }
+
+    /// Graphe de mesures datées dans un fuseau horaire, converties en heure locale du fuseau
+    pub fn depuis_dates<Tz: TimeZone>(
+        mesures: impl IntoIterator<Item = (DateTime<Tz>, f64)>,
+    ) -> Self {
+        Self::new(
+            mesures
+                .into_iter()
+                .map(|(date, valeur)| (date.naive_local(), valeur))
+                .collect(),
+        )
+    }
+
+    /// Courbe sans axes occupant toute sa zone
+    pub fn sparkline(mesures: Vec<(NaiveDateTime, f64)>) -> Self {
+        Self::new(mesures).sans_axes()
+    }
+
+    /// Choisir la forme du graphe
+    pub fn avec_forme(mut self, forme: FormeGraphe) -> Self {
+        self.forme = forme;
+        self
+    }
+
+    /// Choisir la couleur des mesures
+    pub fn avec_couleur(mut self, couleur: TriCouleur) -> Self {
+        self.couleur = couleur;
+        self
+    }
+
+    /// Mettre en évidence un seuil
+    pub fn avec_seuil(mut self, seuil: Seuil) -> Self {
+        self.seuil = Some(seuil);
+        self
+    }
+
+    /// Regrouper les mesures par intervalle : la moyenne de chaque intervalle est dessinée
+    /// au-dessus d'une bande tramée allant du minimum au maximum de l'intervalle
+    pub fn avec_bande_min_max(mut self, intervalle: TimeDelta) -> Self {
+        self.bande_min_max = Some(intervalle);
+        self
+    }
+
+    /// Ne pas dessiner les axes et leurs étiquettes
+    pub fn sans_axes(mut self) -> Self {
+        self.axes = false;
+        self
+    }
+
+    /// Choisir le style, le nombre de décimales des valeurs et le format des dates des
+    /// étiquettes (syntaxe de [`chrono::format::strftime`]). Les dates ne sont pas affichées si
+    /// le format est invalide.
+    pub fn avec_etiquettes(
+        mut self,
+        style: StyleTexte,
+        decimales: usize,
+        format_date: impl Into<String>,
+    ) -> Self {
+        self.style_etiquettes = style;
+        self.decimales = decimales;
+        self.format_date = format_date.into();
+        self
+    }
+
+    /// Fixer les bornes de l'axe des valeurs, calculées à partir des mesures sinon
+    pub fn avec_bornes(mut self, minimum: Option<f64>, maximum: Option<f64>) -> Self {
+        self.minimum = minimum;
+        self.maximum = maximum;
+        self
+    }
+
+    /// Mesures regroupées par intervalle : (date, moyenne, minimum, maximum)
+    fn groupes(&self) -> Vec<(NaiveDateTime, f64, f64, f64)> {
+        let Some(intervalle) = self
+            .bande_min_max
+            .filter(|intervalle| *intervalle > TimeDelta::zero())
+        else {
+            return self
+                .mesures
+                .iter()
+                .map(|(date, valeur)| (*date, *valeur, *valeur, *valeur))
+                .collect();
+        };
+        let Some((debut, _)) = self.mesures.first() else {
+            return Vec::new();
+        };
+
+        let mut groupes: Vec<(NaiveDateTime, f64, f64, f64, usize)> = Vec::new();
+        for (date, valeur) in &self.mesures {
+            let indice = (*date - *debut).num_milliseconds() / intervalle.num_milliseconds().max(1);
+            // Les mesures trop éloignées de la première pour être datées sont ignorées
+            let Some(date_groupe) = i32::try_from(indice)
+                .ok()
+                .and_then(|indice| intervalle.checked_mul(indice))
+                .and_then(|decalage| decalage.checked_add(&(intervalle / 2)))
+                .and_then(|decalage| debut.checked_add_signed(decalage))
+            else {
+                log::warn!("Mesure du {date} trop éloignée de la première pour être regroupée");
+                continue;
+            };
+            match groupes.last_mut() {
+                Some(groupe) if groupe.0 == date_groupe => {
+                    groupe.1 += valeur;
+                    groupe.2 = groupe.2.min(*valeur);
+                    groupe.3 = groupe.3.max(*valeur);
+                    groupe.4 += 1;
+                }
+                _ => groupes.push((date_groupe, *valeur, *valeur, *valeur, 1)),
+            }
+        }
+        groupes
+            .into_iter()
+            .map(|(date, somme, minimum, maximum, nombre)| {
+                (date, somme / nombre as f64, minimum, maximum)
+            })
+            .collect()
+    }
+
+    /// Bornes de l'axe des valeurs, élargies si toutes les valeurs sont égales
+    fn bornes(&self) -> (f64, f64) {
+        let minimum = self.minimum.unwrap_or_else(|| {
+            self.mesures
+                .iter()
+                .map(|(_, valeur)| *valeur)
+                .fold(f64::INFINITY, f64::min)
+        });
+        let maximum = self.maximum.unwrap_or_else(|| {
+            self.mesures
+                .iter()
+                .map(|(_, valeur)| *valeur)
+                .fold(f64::NEG_INFINITY, f64::max)
+        });
+        match maximum > minimum {
+            // Aucune mesure
+            _ if !minimum.is_finite() || !maximum.is_finite() => (0., 1.),
+            true => (minimum, maximum),
+            false => (minimum - 1., minimum + 1.),
+        }
+    }
+
+    /// Dessiner les étiquettes et les axes, et renvoyer la zone restante pour les mesures
+    fn dessiner_axes(
+        &self,
+        contexte: &Contexte,
+        memoire_tampon: &mut MemoireTampon,
+        zone: Zone,
+        (minimum, maximum): (f64, f64),
+    ) -> Zone {
+        let style = self.style_etiquettes;
+        let metriques = contexte.polices.metriques(style.taille);
+        let hauteur_etiquette = (metriques.ascendante - metriques.descendante).ceil() as usize;
+        let etiquettes_valeurs =
+            [maximum, minimum].map(|valeur| format!("{valeur:.*}", self.decimales));
+        let largeur_etiquettes = etiquettes_valeurs
+            .iter()
+            .map(|etiquette| contexte.polices.mesurer(etiquette, style.taille).ceil() as usize)
+            .max()
+            .unwrap_or(0)
+            + 2;
+        if zone.largeur <= largeur_etiquettes + 2 || zone.hauteur <= 2 * hauteur_etiquette + 2 {
+            return zone;
+        }
+        let trace = Zone::new(
+            zone.x + largeur_etiquettes + 1,
+            zone.y + hauteur_etiquette / 2,
+            zone.largeur - largeur_etiquettes - 1,
+            zone.hauteur - hauteur_etiquette - hauteur_etiquette / 2 - 1,
+        );
+
+        let style_valeurs = style.avec_alignement(Alignement::Droite);
+        let zone_maximum = Zone::new(zone.x, zone.y, largeur_etiquettes - 2, hauteur_etiquette);
+        let zone_minimum = Zone::new(
+            zone.x,
+            trace.y + trace.hauteur - hauteur_etiquette / 2,
+            largeur_etiquettes - 2,
+            hauteur_etiquette,
+        );
+        let polices = contexte.polices;
+        polices.dessiner(
+            memoire_tampon,
+            zone_maximum,
+            &etiquettes_valeurs[0],
+            &style_valeurs,
+        );
+        polices.dessiner(
+            memoire_tampon,
+            zone_minimum,
+            &etiquettes_valeurs[1],
+            &style_valeurs,
+        );
+
+        if let (Some((debut, _)), Some((fin, _))) = (self.mesures.first(), self.mesures.last()) {
+            let zone_dates = Zone::new(
+                trace.x,
+                trace.y + trace.hauteur + 1,
+                trace.largeur,
+                hauteur_etiquette,
+            );
+            let style_dates = style.avec_alignement_vertical(AlignementVertical::Bas);
+            if let Some(date) = widget::ecrire_date(debut.format(&self.format_date)) {
+                polices.dessiner(memoire_tampon, zone_dates, &date, &style_dates);
+            }
+            if fin > debut {
+                if let Some(date) = widget::ecrire_date(fin.format(&self.format_date)) {
+                    polices.dessiner(
+                        memoire_tampon,
+                        zone_dates,
+                        &date,
+                        &style_dates.avec_alignement(Alignement::Droite),
+                    );
+                }
+            }
+        }
+
+        for y in trace.y..=trace.y + trace.hauteur {
+            memoire_tampon.definir_pixel(trace.x - 1, y, TriCouleur::Noir);
+        }
+        for x in trace.x..trace.x + trace.largeur {
+            memoire_tampon.definir_pixel(x, trace.y + trace.hauteur, TriCouleur::Noir);
+        }
+        trace
+    }
+}
+
+/// Conversion des dates et des valeurs en coordonnées de la zone de tracé
+struct Repere {
+    zone: Zone,
+    debut: NaiveDateTime,
+    duree: f64,
+    minimum: f64,
+    maximum: f64,
+}
+
+impl Repere {
+    fn x(&self, date: NaiveDateTime) -> i64 {
+        let largeur = self.zone.largeur.saturating_sub(1) as f64;
+        let position = match self.duree > 0. {
+            true => (date - self.debut).num_milliseconds() as f64 / self.duree,
+            false => 0.5,
+        };
+        self.zone.x as i64 + (position * largeur).round() as i64
+    }
+
+    fn y(&self, valeur: f64) -> i64 {
+        let hauteur = self.zone.hauteur.saturating_sub(1) as f64;
+        let position = (valeur - self.minimum) / (self.maximum - self.minimum);
+        self.zone.y as i64 + (hauteur - position.clamp(0., 1.) * hauteur).round() as i64
+    }
+
+    /// Dessiner un pixel s'il est dans la zone de tracé
+    fn pixel(&self, memoire_tampon: &mut MemoireTampon, x: i64, y: i64, couleur: TriCouleur) {
+        let zone = &self.zone;
+        if x >= zone.x as i64
+            && y >= zone.y as i64
+            && x < (zone.x + zone.largeur) as i64
+            && y < (zone.y + zone.hauteur) as i64
+        {
+            memoire_tampon.definir_pixel(x as usize, y as usize, couleur);
+        }
+    }
+}
+
+impl Widget for Graphe {
+    fn mesurer(&self, _contexte: &Contexte, largeur: usize, hauteur: usize) -> (usize, usize) {
+        (largeur, hauteur)
+    }
+
+    fn dessiner(&self, contexte: &Contexte, memoire_tampon: &mut MemoireTampon, zone: Zone) {
+        let bornes = self.bornes();
+        let trace = match self.axes {
+            true => self.dessiner_axes(contexte, memoire_tampon, zone, bornes),
+            false => zone,
+        };
+        let (Some((debut, _)), Some((fin, _))) = (self.mesures.first(), self.mesures.last()) else {
+            return;
+        };
+        if trace.est_vide() {
+            return;
+        }
+        let repere = Repere {
+            zone: trace,
+            debut: *debut,
+            duree: (*fin - *debut).num_milliseconds() as f64,
+            minimum: bornes.0,
+            maximum: bornes.1,
+        };
+        let couleur = |valeur: f64| match self.seuil {
+            Some(seuil) if seuil.est_depasse(valeur) => seuil.couleur,
+            _ => self.couleur,
+        };
+        let groupes = self.groupes();
+
+        // Bande tramée entre le minimum et le maximum de chaque intervalle
+        if self.bande_min_max.is_some() {
+            let mut precedent: Option<(i64, i64, i64)> = None;
+            for (date, _, minimum, maximum) in &groupes {
+                let courant = (repere.x(*date), repere.y(*maximum), repere.y(*minimum));
+                let (x_debut, haut_debut, bas_debut) = precedent.unwrap_or(courant);
+                for x in x_debut..=courant.0 {
+                    let t = match courant.0 > x_debut {
+                        true => (x - x_debut) as f64 / (courant.0 - x_debut) as f64,
+                        false => 1.,
+                    };
+                    let haut = haut_debut + ((courant.1 - haut_debut) as f64 * t).round() as i64;
+                    let bas = bas_debut + ((courant.2 - bas_debut) as f64 * t).round() as i64;
+                    for y in haut..=bas {
+                        if (x + y) % 2 == 0 {
+                            repere.pixel(memoire_tampon, x, y, self.couleur);
+                        }
+                    }
+                }
+                precedent = Some(courant);
+            }
+        }
+
+        match self.forme {
+            FormeGraphe::Courbe => {
+                let mut precedent: Option<(i64, i64, f64)> = None;
+                for (date, valeur, _, _) in &groupes {
+                    let courant = (repere.x(*date), repere.y(*valeur), *valeur);
+                    let (x0, y0, valeur0) = precedent.unwrap_or(courant);
+                    tracer_ligne((x0, y0), (courant.0, courant.1), |x, y, t| {
+                        let valeur = valeur0 + (courant.2 - valeur0) * t;
+                        repere.pixel(memoire_tampon, x, y, couleur(valeur));
+                    });
+                    precedent = Some(courant);
+                }
+            }
+            FormeGraphe::Barres => {
+                let largeur_barre = (trace.largeur / groupes.len().max(1))
+                    .saturating_sub(1)
+                    .max(1) as i64;
+                let base = repere.y(0_f64.clamp(bornes.0, bornes.1));
+                for (date, valeur, _, _) in &groupes {
+                    let x_centre = repere.x(*date);
+                    let x_debut = (x_centre - largeur_barre / 2).clamp(
+                        trace.x as i64,
+                        (trace.x + trace.largeur) as i64 - largeur_barre,
+                    );
+                    let y = repere.y(*valeur);
+                    for x in x_debut..x_debut + largeur_barre {
+                        for y in y.min(base)..=y.max(base) {
+                            repere.pixel(memoire_tampon, x, y, couleur(*valeur));
+                        }
+                    }
+                }
+            }
+        }
+
+        if let Some(seuil) = self.seuil {
+            if seuil.valeur >= bornes.0 && seuil.valeur <= bornes.1 {
+                let y = repere.y(seuil.valeur);
+                for x in trace.x as i64..(trace.x + trace.largeur) as i64 {
+                    if (x - trace.x as i64) % (2 * LONGUEUR_TIRET) < LONGUEUR_TIRET {
+                        repere.pixel(memoire_tampon, x, y, seuil.couleur);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Tracer un segment avec l'algorithme de Bresenham. Le rappel reçoit les coordonnées de
+/// chaque pixel et sa position sur le segment entre 0 et 1.
+fn tracer_ligne((x0, y0): (i64, i64), (x1, y1): (i64, i64), mut pixel: impl FnMut(i64, i64, f64)) {
+    let dx = (x1 - x0).abs();
+    let dy = -(y1 - y0).abs();
+    let pas_x = if x0 < x1 { 1 } else { -1 };
+    let pas_y = if y0 < y1 { 1 } else { -1 };
+    let longueur = dx.max(-dy).max(1) as f64;
+    let (mut x, mut y) = (x0, y0);
+    let mut erreur = dx + dy;
+    for i in 0.. {
+        pixel(x, y, i as f64 / longueur);
+        if x == x1 && y == y1 {
+            break;
+        }
+        let double = 2 * erreur;
+        if double >= dy {
+            erreur += dy;
+            x += pas_x;
+        }
+        if double <= dx {
+            erreur += dx;
+            y += pas_y;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+    use crate::ecran::texte::Polices;
+
+    fn date(annee: i32, heure: u32, minute: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(annee, 1, 1)
+            .unwrap()
+            .and_hms_opt(heure, minute, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn groupes_par_intervalle() {
+        let mesures = vec![
+            (date(2024, 10, 0), 1.),
+            (date(2024, 10, 20), 3.),
+            (date(2024, 11, 10), 5.),
+        ];
+        let graphe = Graphe::new(mesures).avec_bande_min_max(TimeDelta::hours(1));
+        assert_eq!(
+            graphe.groupes(),
+            [
+                (date(2024, 10, 30), 2., 1., 3.),
+                (date(2024, 11, 30), 5., 5., 5.)
+            ]
+        );
+    }
+
+    #[test]
+    fn groupes_trop_eloignes() {
+        // Plus de i32::MAX intervalles de deux millisecondes entre les mesures
+        let mesures = vec![(date(2000, 0, 0), 1.), (date(2024, 0, 0), 2.)];
+        let graphe = Graphe::new(mesures).avec_bande_min_max(TimeDelta::milliseconds(2));
+        let milieu = date(2000, 0, 0) + TimeDelta::milliseconds(1);
+        assert_eq!(graphe.groupes(), [(milieu, 1., 1., 1.)]);
+
+        // Décalage dépassant la plage des dates
+        let mesures = vec![(date(2000, 0, 0), 1.), (date(2024, 0, 0), 2.)];
+        let graphe = Graphe::new(mesures).avec_bande_min_max(TimeDelta::days(365 * 600_000));
+        assert!(graphe.groupes().is_empty());
+    }
+
+    #[test]
+    fn format_de_date_invalide() {
+        let polices = Polices::depuis_fichier(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/polices/DejaVuSansMono.ttf"
+        ))
+        .unwrap();
+        let contexte = Contexte::new(&polices, date(2024, 12, 0));
+        let mesures = vec![(date(2024, 10, 0), 1.), (date(2024, 11, 0), 2.)];
+        for format in ["%Q", "%Z"] {
+            let graphe =
+                Graphe::new(mesures.clone()).avec_etiquettes(StyleTexte::new(14.), 0, format);
+            let mut memoire_tampon = MemoireTampon::new(200, 100);
+            graphe.afficher(&contexte, &mut memoire_tampon);
+        }
+    }
+}
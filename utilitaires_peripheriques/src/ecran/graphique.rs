@@ -0,0 +1,246 @@
+use core::convert::Infallible;
+
+use embedded_graphics_core::{
+    pixelcolor::{
+        raw::{RawData, RawU2},
+        BinaryColor, PixelColor,
+    },
+    prelude::{DrawTarget, OriginDimensions, Pixel, Size},
+};
+
+use crate::ecran::{
+    ecran::Ecran,
+    materiel::{BrocheEntree, BrocheSortie, LiaisonSpi},
+    memoire_tampon::{MemoireTampon, TriCouleur},
+    modele::ModeleEcran,
+    virtuel::EcranVirtuel,
+};
+
+impl PixelColor for TriCouleur {
+    type Raw = RawU2;
+}
+
+impl From<RawU2> for TriCouleur {
+    fn from(valeur: RawU2) -> Self {
+        match valeur.into_inner() {
+            0 => TriCouleur::Blanc,
+            1 => TriCouleur::Noir,
+            _ => TriCouleur::Rouge,
+        }
+    }
+}
+
+impl From<TriCouleur> for RawU2 {
+    fn from(couleur: TriCouleur) -> Self {
+        match couleur {
+            TriCouleur::Blanc => RawU2::new(0),
+            TriCouleur::Noir => RawU2::new(1),
+            TriCouleur::Rouge => RawU2::new(2),
+        }
+    }
+}
+
+impl From<BinaryColor> for TriCouleur {
+    fn from(couleur: BinaryColor) -> Self {
+        match couleur {
+            BinaryColor::On => TriCouleur::Noir,
+            BinaryColor::Off => TriCouleur::Blanc,
+        }
+    }
+}
+
+impl DrawTarget for MemoireTampon {
+    type Color = TriCouleur;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(point, couleur) in pixels {
+            if point.x >= 0 && point.y >= 0 {
+                self.definir_pixel(point.x as usize, point.y as usize, couleur);
+            }
+        }
+        Ok(())
+    }
+
+    fn clear(&mut self, couleur: Self::Color) -> Result<(), Self::Error> {
+        self.effacer(couleur);
+        Ok(())
+    }
+}
+
+impl OriginDimensions for MemoireTampon {
+    fn size(&self) -> Size {
+        Size::new(self.largeur() as u32, self.hauteur() as u32)
+    }
+}
+
+impl<M: ModeleEcran, S: LiaisonSpi, O: BrocheSortie, I: BrocheEntree> DrawTarget
+    for Ecran<M, S, O, I>
+{
+    type Color = TriCouleur;
+    type Error = Infallible;
+
+    fn draw_iter<P>(&mut self, pixels: P) -> Result<(), Self::Error>
+    where
+        P: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        self.memoire_tampon_mut().draw_iter(pixels)
+    }
+
+    fn clear(&mut self, couleur: Self::Color) -> Result<(), Self::Error> {
+        self.memoire_tampon_mut().clear(couleur)
+    }
+}
+
+impl<M: ModeleEcran, S: LiaisonSpi, O: BrocheSortie, I: BrocheEntree> OriginDimensions
+    for Ecran<M, S, O, I>
+{
+    fn size(&self) -> Size {
+        self.memoire_tampon().size()
+    }
+}
+
+impl<M: ModeleEcran> DrawTarget for EcranVirtuel<M> {
+    type Color = TriCouleur;
+    type Error = Infallible;
+
+    fn draw_iter<P>(&mut self, pixels: P) -> Result<(), Self::Error>
+    where
+        P: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        self.memoire_tampon_mut().draw_iter(pixels)
+    }
+
+    fn clear(&mut self, couleur: Self::Color) -> Result<(), Self::Error> {
+        self.memoire_tampon_mut().clear(couleur)
+    }
+}
+
+impl<M: ModeleEcran> OriginDimensions for EcranVirtuel<M> {
+    fn size(&self) -> Size {
+        self.memoire_tampon().size()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use embedded_graphics_core::{geometry::Point, primitives::Rectangle};
+
+    use super::*;
+    use crate::ecran::{rotation::Rotation, simulation::ControleurSimule};
+
+    /// Mémoire tampon de 16×8 pixels physiques tournée de 90°, soit 8×16 pixels logiques
+    fn tournee() -> MemoireTampon {
+        let mut memoire_tampon = MemoireTampon::new(16, 8);
+        memoire_tampon.definir_rotation(Rotation::R90);
+        memoire_tampon
+    }
+
+    #[test]
+    fn rectangle_selon_rotation() {
+        let mut memoire_tampon = tournee();
+        assert_eq!(memoire_tampon.size(), Size::new(8, 16));
+
+        let rectangle = Rectangle::new(Point::new(0, 0), Size::new(2, 3));
+        memoire_tampon
+            .fill_solid(&rectangle, TriCouleur::Rouge)
+            .unwrap();
+        memoire_tampon
+            .draw_iter([Pixel(Point::new(7, 15), TriCouleur::Noir)])
+            .unwrap();
+
+        // Pixels logiques (x, y) placés en (15 - y, x) : colonnes physiques 13 à 15 des
+        // deux premières lignes en rouge, pixel physique (0, 7) en noir
+        let mut plan_noir = vec![0xFF; 16];
+        plan_noir[14] = 0x7F;
+        let mut plan_rouge = vec![0x00; 16];
+        plan_rouge[1] = 0x07;
+        plan_rouge[3] = 0x07;
+        assert_eq!(memoire_tampon.plan_noir(), plan_noir);
+        assert_eq!(memoire_tampon.plan_rouge(), plan_rouge);
+        assert_eq!(memoire_tampon.pixel(1, 2), Some(TriCouleur::Rouge));
+        assert_eq!(memoire_tampon.pixel(2, 2), Some(TriCouleur::Blanc));
+    }
+
+    #[test]
+    fn pixels_hors_ecran_ignores() {
+        let mut memoire_tampon = tournee();
+        memoire_tampon
+            .draw_iter([
+                Pixel(Point::new(-1, 0), TriCouleur::Noir),
+                Pixel(Point::new(0, -1), TriCouleur::Noir),
+                Pixel(Point::new(8, 0), TriCouleur::Noir),
+                Pixel(Point::new(0, 16), TriCouleur::Noir),
+            ])
+            .unwrap();
+        assert_eq!(memoire_tampon, tournee());
+
+        // Rectangle dépassant du coin inférieur droit : seuls 2×2 pixels sont dessinés
+        let rectangle = Rectangle::new(Point::new(6, 14), Size::new(4, 4));
+        memoire_tampon
+            .fill_solid(&rectangle, TriCouleur::Noir)
+            .unwrap();
+        let noirs = memoire_tampon
+            .plan_noir()
+            .iter()
+            .map(|octet| octet.count_zeros())
+            .sum::<u32>();
+        assert_eq!(noirs, 4);
+        assert_eq!(memoire_tampon.pixel(7, 15), Some(TriCouleur::Noir));
+    }
+
+    #[test]
+    fn conversions_de_couleur() {
+        for couleur in [TriCouleur::Blanc, TriCouleur::Noir, TriCouleur::Rouge] {
+            assert_eq!(TriCouleur::from(RawU2::from(couleur)), couleur);
+        }
+        assert_eq!(TriCouleur::from(RawU2::new(3)), TriCouleur::Rouge);
+        assert_eq!(TriCouleur::from(BinaryColor::On), TriCouleur::Noir);
+        assert_eq!(TriCouleur::from(BinaryColor::Off), TriCouleur::Blanc);
+    }
+
+    #[test]
+    fn dessin_sur_l_ecran() {
+        let controleur = ControleurSimule::new();
+        let mut ecran = controleur.ecran();
+        ecran.definir_rotation(Rotation::R90);
+        assert_eq!(ecran.size(), Size::new(480, 800));
+
+        let rectangle = Rectangle::new(Point::new(470, 790), Size::new(20, 20));
+        ecran.fill_solid(&rectangle, TriCouleur::Rouge).unwrap();
+        assert_eq!(ecran.pixel(479, 799), Some(TriCouleur::Rouge));
+        assert_eq!(ecran.pixel(469, 799), Some(TriCouleur::Blanc));
+
+        ecran.clear(TriCouleur::Noir).unwrap();
+        assert!(ecran
+            .memoire_tampon()
+            .plan_noir()
+            .iter()
+            .all(|octet| *octet == 0));
+        assert!(ecran
+            .memoire_tampon()
+            .plan_rouge()
+            .iter()
+            .all(|octet| *octet == 0));
+    }
+
+    #[test]
+    fn dessin_sur_l_ecran_virtuel() {
+        let mut ecran: EcranVirtuel = EcranVirtuel::new("ecran.pbm").unwrap();
+        ecran.definir_rotation(Rotation::R270);
+        assert_eq!(ecran.size(), Size::new(480, 800));
+
+        ecran
+            .draw_iter([
+                Pixel(Point::new(0, 0), TriCouleur::Noir),
+                Pixel(Point::new(480, 0), TriCouleur::Noir),
+            ])
+            .unwrap();
+        assert_eq!(ecran.pixel(0, 0), Some(TriCouleur::Noir));
+        // Origine logique tournée de 270° : premier pixel de la dernière ligne physique
+        assert_eq!(ecran.memoire_tampon().plan_noir()[479 * 100], 0x7F);
+    }
+}
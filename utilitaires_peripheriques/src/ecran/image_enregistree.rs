@@ -0,0 +1,261 @@
+use std::{
+    fs,
+    path::Path,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::ecran::{erreur::ErreurEcran, memoire_tampon::MemoireTampon, modele::ModeleEcran};
+
+/// Signature des fichiers d'image enregistrée
+const SIGNATURE: &[u8; 4] = b"EPDI";
+
+/// Version du format des fichiers d'image enregistrée
+const VERSION: u8 = 1;
+
+/// Dernière image affichée par l'écran, enregistrée pour être restaurée au redémarrage
+///
+/// Format du fichier (entiers big-endian) : signature `EPDI`, version, longueur et nom du
+/// modèle, largeur et hauteur physiques sur 16 bits, horodatage en secondes depuis l'époque
+/// Unix sur 64 bits, puis les plans noir et rouge.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ImageEnregistree {
+    modele: String,
+    horodatage: SystemTime,
+    memoire_tampon: MemoireTampon,
+}
+
+impl ImageEnregistree {
+    /// Image affichée par un modèle d'écran à l'instant présent
+    pub fn new(modele: &str, memoire_tampon: MemoireTampon) -> Self {
+        Self {
+            modele: modele.to_string(),
+            horodatage: SystemTime::now(),
+            memoire_tampon,
+        }
+    }
+
+    /// Nom du modèle d'écran ([`crate::ecran::modele::ModeleEcran::NOM`])
+    pub fn modele(&self) -> &str {
+        &self.modele
+    }
+
+    /// Date de l'affichage de l'image, à la seconde près
+    pub fn horodatage(&self) -> SystemTime {
+        self.horodatage
+    }
+
+    /// Plans noir et rouge de l'image, selon les dimensions physiques de l'écran
+    pub fn memoire_tampon(&self) -> &MemoireTampon {
+        &self.memoire_tampon
+    }
+
+    /// Récupérer les plans de l'image
+    pub fn into_memoire_tampon(self) -> MemoireTampon {
+        self.memoire_tampon
+    }
+
+    /// Encoder l'image
+    pub fn en_octets(&self) -> Vec<u8> {
+        let secondes = self
+            .horodatage
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let modele = &self.modele.as_bytes()[..self.modele.len().min(u8::MAX as usize)];
+        let plan_noir = self.memoire_tampon.plan_noir();
+        let plan_rouge = self.memoire_tampon.plan_rouge();
+
+        let mut octets = Vec::with_capacity(
+            SIGNATURE.len() + 2 + modele.len() + 12 + plan_noir.len() + plan_rouge.len(),
+        );
+        octets.extend_from_slice(SIGNATURE);
+        octets.push(VERSION);
+        octets.push(modele.len() as u8);
+        octets.extend_from_slice(modele);
+        octets.extend_from_slice(&(self.memoire_tampon.largeur_physique() as u16).to_be_bytes());
+        octets.extend_from_slice(&(self.memoire_tampon.hauteur_physique() as u16).to_be_bytes());
+        octets.extend_from_slice(&secondes.to_be_bytes());
+        octets.extend_from_slice(plan_noir);
+        octets.extend_from_slice(plan_rouge);
+        octets
+    }
+
+    /// Décoder une image encodée par [`Self::en_octets`]
+    pub fn depuis_octets(octets: &[u8]) -> Result<Self, ErreurEcran> {
+        Self::decoder(octets, |_, _| Ok(()))
+    }
+
+    /// Décoder une image encodée par [`Self::en_octets`] pour un modèle d'écran.
+    /// Le nom du modèle et les dimensions sont vérifiés avant la lecture des plans.
+    pub fn depuis_octets_modele<M: ModeleEcran>(octets: &[u8]) -> Result<Self, ErreurEcran> {
+        Self::decoder(octets, |modele, dimensions| {
+            if modele != M::NOM {
+                return Err(ErreurEcran::ModeleIncompatible {
+                    attendu: M::NOM,
+                    recu: modele.to_string(),
+                });
+            }
+            if dimensions != (M::LARGEUR, M::HAUTEUR) {
+                return Err(ErreurEcran::DimensionsInvalides {
+                    attendues: (M::LARGEUR, M::HAUTEUR),
+                    recues: dimensions,
+                });
+            }
+            Ok(())
+        })
+    }
+
+    /// Décoder une image après avoir vérifié le modèle et les dimensions (largeur, hauteur) de
+    /// l'en-tête
+    fn decoder(
+        octets: &[u8],
+        verifier: impl FnOnce(&str, (usize, usize)) -> Result<(), ErreurEcran>,
+    ) -> Result<Self, ErreurEcran> {
+        let mut lecteur = Lecteur(octets);
+        if lecteur.lire(SIGNATURE.len())? != SIGNATURE || lecteur.lire(1)? != [VERSION] {
+            return Err(ErreurEcran::ImageEnregistreeInvalide);
+        }
+        let longueur_modele = lecteur.lire(1)?[0] as usize;
+        let modele = String::from_utf8(lecteur.lire(longueur_modele)?.to_vec())
+            .map_err(|_| ErreurEcran::ImageEnregistreeInvalide)?;
+        let largeur = u16::from_be_bytes(lecteur.lire_tableau()?) as usize;
+        let hauteur = u16::from_be_bytes(lecteur.lire_tableau()?) as usize;
+        let secondes = u64::from_be_bytes(lecteur.lire_tableau()?);
+        verifier(&modele, (largeur, hauteur))?;
+
+        // Les dimensions de l'en-tête ne sont pas fiables : la taille des plans est vérifiée
+        // avant d'allouer la mémoire tampon
+        let taille_plan = largeur.div_ceil(8) * hauteur;
+        if lecteur.0.len() != 2 * taille_plan {
+            return Err(ErreurEcran::ImageEnregistreeInvalide);
+        }
+        let mut memoire_tampon = MemoireTampon::new(largeur, hauteur);
+        memoire_tampon
+            .plan_noir_mut()
+            .copy_from_slice(lecteur.lire(taille_plan)?);
+        memoire_tampon
+            .plan_rouge_mut()
+            .copy_from_slice(lecteur.lire(taille_plan)?);
+
+        Ok(Self {
+            modele,
+            horodatage: UNIX_EPOCH + Duration::from_secs(secondes),
+            memoire_tampon,
+        })
+    }
+
+    /// Lire une image enregistrée dans un fichier
+    pub fn lire(chemin: impl AsRef<Path>) -> Result<Self, ErreurEcran> {
+        Self::depuis_octets(&fs::read(chemin)?)
+    }
+
+    /// Lire une image enregistrée dans un fichier pour un modèle d'écran
+    pub fn lire_modele<M: ModeleEcran>(chemin: impl AsRef<Path>) -> Result<Self, ErreurEcran> {
+        Self::depuis_octets_modele::<M>(&fs::read(chemin)?)
+    }
+
+    /// Enregistrer l'image dans un fichier.
+    /// Le fichier est d'abord écrit à côté puis renommé, pour ne pas être tronqué en cas de
+    /// coupure de courant.
+    pub fn ecrire(&self, chemin: impl AsRef<Path>) -> Result<(), ErreurEcran> {
+        let chemin = chemin.as_ref();
+        let mut chemin_temporaire = chemin.as_os_str().to_owned();
+        chemin_temporaire.push(".tmp");
+        fs::write(&chemin_temporaire, self.en_octets())?;
+        fs::rename(&chemin_temporaire, chemin)?;
+        Ok(())
+    }
+}
+
+/// Lecture séquentielle des octets d'une image enregistrée
+struct Lecteur<'a>(&'a [u8]);
+
+impl<'a> Lecteur<'a> {
+    fn lire(&mut self, longueur: usize) -> Result<&'a [u8], ErreurEcran> {
+        if self.0.len() < longueur {
+            return Err(ErreurEcran::ImageEnregistreeInvalide);
+        }
+        let (octets, reste) = self.0.split_at(longueur);
+        self.0 = reste;
+        Ok(octets)
+    }
+
+    fn lire_tableau<const N: usize>(&mut self) -> Result<[u8; N], ErreurEcran> {
+        let mut tableau = [0; N];
+        tableau.copy_from_slice(self.lire(N)?);
+        Ok(tableau)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecran::{
+        memoire_tampon::TriCouleur,
+        modele::{Modele4In2B, Modele7In5BV2},
+    };
+
+    fn image() -> ImageEnregistree {
+        let mut memoire_tampon = MemoireTampon::new(Modele4In2B::LARGEUR, Modele4In2B::HAUTEUR);
+        memoire_tampon.definir_pixel(0, 0, TriCouleur::Noir);
+        memoire_tampon.definir_pixel(399, 299, TriCouleur::Rouge);
+        let mut image = ImageEnregistree::new(Modele4In2B::NOM, memoire_tampon);
+        image.horodatage = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        image
+    }
+
+    #[test]
+    fn aller_retour() {
+        let image = image();
+        let octets = image.en_octets();
+        assert_eq!(ImageEnregistree::depuis_octets(&octets).unwrap(), image);
+        let decodee = ImageEnregistree::depuis_octets_modele::<Modele4In2B>(&octets).unwrap();
+        assert_eq!(decodee, image);
+        assert_eq!(
+            decodee.memoire_tampon().pixel(399, 299),
+            Some(TriCouleur::Rouge)
+        );
+    }
+
+    #[test]
+    fn fichier_tronque() {
+        let octets = image().en_octets();
+        for longueur in [0, 4, 10, 20, octets.len() - 15000, octets.len() - 1] {
+            assert!(matches!(
+                ImageEnregistree::depuis_octets(&octets[..longueur]),
+                Err(ErreurEcran::ImageEnregistreeInvalide)
+            ));
+        }
+        let mut octets = octets;
+        octets.push(0);
+        assert!(ImageEnregistree::depuis_octets(&octets).is_err());
+    }
+
+    #[test]
+    fn dimensions_invalides() {
+        // En-tête annonçant 65535×65535 pixels sans les plans correspondants
+        let mut octets = image().en_octets();
+        let debut = SIGNATURE.len() + 2 + Modele4In2B::NOM.len();
+        octets[debut..debut + 4].fill(0xFF);
+        assert!(matches!(
+            ImageEnregistree::depuis_octets(&octets),
+            Err(ErreurEcran::ImageEnregistreeInvalide)
+        ));
+        assert!(matches!(
+            ImageEnregistree::depuis_octets_modele::<Modele4In2B>(&octets),
+            Err(ErreurEcran::DimensionsInvalides {
+                recues: (65535, 65535),
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn modele_incompatible() {
+        let octets = image().en_octets();
+        assert!(matches!(
+            ImageEnregistree::depuis_octets_modele::<Modele7In5BV2>(&octets),
+            Err(ErreurEcran::ModeleIncompatible { .. })
+        ));
+    }
+}
@@ -1,18 +1,104 @@
 /// Commandes pour contrôler l'affichage de l'écran
-#[derive(Copy, Clone)]
-pub(crate) enum Instruction {
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Instruction {
+    /// Réglage de l'alimentation
     PowerSetting = 0x01,
+    /// Mise sous tension
     PowerOn = 0x04,
+    /// Démarrage progressif du convertisseur
+    BoosterSoftStart = 0x06,
+    /// Lecture de l'état du contrôleur
     BusyStatus = 0x71,
+    /// Réglage du panneau
     PanelSetting = 0x00,
+    /// Résolution
     Tres = 0x61,
+    /// Commande 0x15
     Unknown1 = 0x15,
+    /// Réglage de l'intervalle VCOM et données
     VcomAndDataIntervalSetting = 0x50,
+    /// Réglage TCON
     TconSetting = 0x60,
-    Unknown2=0x65,
-    PowerOff=0x02,
-    DeepSleep=0x07,
-    DataStartTransmission1=0x10,
-    DataStartTransmission2=0x13,
-    DisplayRefresh=0x12
+    /// Commande 0x65
+    Unknown2 = 0x65,
+    /// Mise hors tension
+    PowerOff = 0x02,
+    /// Veille profonde
+    DeepSleep = 0x07,
+    /// Début de la transmission du premier plan (noir)
+    DataStartTransmission1 = 0x10,
+    /// Début de la transmission du second plan (rouge)
+    DataStartTransmission2 = 0x13,
+    /// Rafraîchissement de l'écran
+    DisplayRefresh = 0x12,
+    /// Fenêtre de mise à jour partielle
+    PartialWindow = 0x90,
+    /// Entrée en mode de mise à jour partielle
+    PartialIn = 0x91,
+    /// Sortie du mode de mise à jour partielle
+    PartialOut = 0x92,
+    /// Lecture du capteur de température
+    TemperatureSensorCalibration = 0x40,
+    /// Choix du capteur de température (interne ou externe)
+    TemperatureSensorSelection = 0x41,
+    /// Table de formes d'onde VCOM
+    LutVcom = 0x20,
+    /// Table de formes d'onde de la transition blanc→blanc
+    LutWhiteWhite = 0x21,
+    /// Table de formes d'onde de la transition noir→blanc
+    LutBlackWhite = 0x22,
+    /// Table de formes d'onde de la transition blanc→noir
+    LutWhiteBlack = 0x23,
+    /// Table de formes d'onde de la transition noir→noir
+    LutBlackBlack = 0x24,
+    /// Réglage de la cascade, dont l'utilisation de la température imposée
+    CascadeSetting = 0xE0,
+    /// Température imposée au contrôleur pour choisir les formes d'onde
+    ForceTemperature = 0xE5,
+}
+
+impl Instruction {
+    /// Instructions connues
+    const INSTRUCTIONS: &'static [Instruction] = &[
+        Instruction::PowerSetting,
+        Instruction::PowerOn,
+        Instruction::BoosterSoftStart,
+        Instruction::BusyStatus,
+        Instruction::PanelSetting,
+        Instruction::Tres,
+        Instruction::Unknown1,
+        Instruction::VcomAndDataIntervalSetting,
+        Instruction::TconSetting,
+        Instruction::Unknown2,
+        Instruction::PowerOff,
+        Instruction::DeepSleep,
+        Instruction::DataStartTransmission1,
+        Instruction::DataStartTransmission2,
+        Instruction::DisplayRefresh,
+        Instruction::PartialWindow,
+        Instruction::PartialIn,
+        Instruction::PartialOut,
+        Instruction::TemperatureSensorCalibration,
+        Instruction::TemperatureSensorSelection,
+        Instruction::LutVcom,
+        Instruction::LutWhiteWhite,
+        Instruction::LutBlackWhite,
+        Instruction::LutWhiteBlack,
+        Instruction::LutBlackBlack,
+        Instruction::CascadeSetting,
+        Instruction::ForceTemperature,
+    ];
+
+    /// Code de l'instruction envoyé au contrôleur
+    pub fn code(&self) -> u8 {
+        *self as u8
+    }
+
+    /// Déterminer l'instruction correspondant à un code reçu par le contrôleur
+    pub fn determiner(code: u8) -> Option<Instruction> {
+        Self::INSTRUCTIONS
+            .iter()
+            .find(|instruction| instruction.code() == code)
+            .copied()
+    }
 }
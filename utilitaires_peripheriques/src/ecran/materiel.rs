@@ -0,0 +1,87 @@
+use rppal::{
+    gpio::{InputPin, OutputPin, Trigger},
+    spi::Spi,
+};
+
+/// Liaison SPI utilisée pour transmettre les instructions et les données au contrôleur de l'écran
+pub trait LiaisonSpi {
+    /// Erreur renvoyée par la liaison
+    type Erreur: std::error::Error + Send + Sync + 'static;
+
+    /// Ecrire les octets sur la liaison
+    fn ecrire(&mut self, donnees: &[u8]) -> Result<(), Self::Erreur>;
+}
+
+/// Lecture sur la liaison SPI
+///
+/// Nécessaire pour lire le capteur de température du contrôleur. Le contrôleur répond sur la
+/// broche DIN : la lecture demande une liaison bidirectionnelle (3 fils) ou une carte reliant
+/// la sortie du contrôleur à la broche MISO du Raspberry Pi.
+pub trait LectureSpi: LiaisonSpi {
+    /// Lire des octets sur la liaison
+    fn lire(&mut self, tampon: &mut [u8]) -> Result<(), Self::Erreur>;
+}
+
+/// Broche de sortie (DC, RST, CS)
+pub trait BrocheSortie {
+    /// Passer la broche au niveau haut
+    fn mettre_niveau_haut(&mut self);
+
+    /// Passer la broche au niveau bas
+    fn mettre_niveau_bas(&mut self);
+}
+
+/// Broche d'entrée (BUSY)
+pub trait BrocheEntree {
+    /// Indiquer si la broche est au niveau bas
+    fn est_niveau_bas(&self) -> bool;
+
+    /// Appeler `rappel` à chaque changement de niveau de la broche.
+    /// Renvoie faux si la broche ne prend pas en charge les interruptions : l'attente de la
+    /// disponibilité du contrôleur se fait alors par scrutation.
+    fn surveiller_fronts(&mut self, _rappel: Box<dyn FnMut() + Send>) -> bool {
+        false
+    }
+}
+
+impl LiaisonSpi for Spi {
+    type Erreur = rppal::spi::Error;
+
+    fn ecrire(&mut self, donnees: &[u8]) -> Result<(), Self::Erreur> {
+        self.write(donnees)?;
+        Ok(())
+    }
+}
+
+impl LectureSpi for Spi {
+    fn lire(&mut self, tampon: &mut [u8]) -> Result<(), Self::Erreur> {
+        self.read(tampon)?;
+        Ok(())
+    }
+}
+
+impl BrocheSortie for OutputPin {
+    fn mettre_niveau_haut(&mut self) {
+        self.set_high();
+    }
+
+    fn mettre_niveau_bas(&mut self) {
+        self.set_low();
+    }
+}
+
+impl BrocheEntree for InputPin {
+    fn est_niveau_bas(&self) -> bool {
+        self.is_low()
+    }
+
+    fn surveiller_fronts(&mut self, mut rappel: Box<dyn FnMut() + Send>) -> bool {
+        match self.set_async_interrupt(Trigger::Both, None, move |_| rappel()) {
+            Ok(()) => true,
+            Err(erreur) => {
+                log::warn!("Interruption indisponible sur la broche BUSY : {erreur}");
+                false
+            }
+        }
+    }
+}
@@ -0,0 +1,420 @@
+use std::cmp;
+
+use crate::ecran::{erreur::ErreurEcran, modele::Plan, rotation::Rotation, zone::Zone};
+
+/// Couleurs affichables par l'écran
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "mise_en_page",
+    derive(serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum TriCouleur {
+    /// Blanc
+    #[default]
+    Blanc,
+    /// Noir
+    Noir,
+    /// Rouge
+    Rouge,
+}
+
+/// Niveaux de gris affichables par les écrans noir et blanc avec le réglage
+/// [`crate::ecran::table_forme::TableForme::gris_4_niveaux`]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Gris4 {
+    /// Noir
+    Noir,
+    /// Gris foncé
+    GrisFonce,
+    /// Gris clair
+    GrisClair,
+    /// Blanc
+    #[default]
+    Blanc,
+}
+
+impl Gris4 {
+    /// Niveaux du plus foncé au plus clair
+    pub const NIVEAUX: [Gris4; 4] = [Gris4::Noir, Gris4::GrisFonce, Gris4::GrisClair, Gris4::Blanc];
+
+    /// Niveau le plus proche d'une luminance sur 8 bits (0 pour le noir, 255 pour le blanc)
+    pub fn depuis_luminance(luminance: u8) -> Self {
+        Self::NIVEAUX[(luminance as usize * 3 + 127) / 255]
+    }
+
+    /// Luminance sur 8 bits du niveau
+    pub fn luminance(&self) -> u8 {
+        match self {
+            Gris4::Noir => 0,
+            Gris4::GrisFonce => 85,
+            Gris4::GrisClair => 170,
+            Gris4::Blanc => 255,
+        }
+    }
+}
+
+/// Mémoire tampon de l'écran composée d'un plan noir et d'un plan rouge
+///
+/// Plan noir : un bit par pixel, 0 pour un pixel noir, 1 pour un pixel blanc.
+/// Plan rouge : un bit par pixel, 1 pour un pixel rouge.
+///
+/// En 4 niveaux de gris ([`Gris4`]), le plan noir vaut 0 pour le noir et le gris foncé, et le
+/// plan rouge vaut 1 pour les deux gris. Un pixel rouge est donc affiché en gris clair.
+///
+/// Les plans sont organisés selon les dimensions physiques de l'écran. Les pixels sont
+/// adressés en coordonnées logiques : l'image est retournée horizontalement si le miroir est
+/// activé, puis tournée selon la [`Rotation`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MemoireTampon {
+    largeur: usize,
+    hauteur: usize,
+    noir: Vec<u8>,
+    rouge: Vec<u8>,
+    rotation: Rotation,
+    miroir: bool,
+}
+
+impl MemoireTampon {
+    /// Mémoire tampon blanche
+    pub fn new(largeur: usize, hauteur: usize) -> Self {
+        let taille = largeur.div_ceil(8) * hauteur;
+        Self {
+            largeur,
+            hauteur,
+            noir: vec![0xFF; taille],
+            rouge: vec![0x00; taille],
+            rotation: Rotation::R0,
+            miroir: false,
+        }
+    }
+
+    /// Largeur logique en pixels, selon la rotation
+    pub fn largeur(&self) -> usize {
+        match self.rotation.echange_dimensions() {
+            true => self.hauteur,
+            false => self.largeur,
+        }
+    }
+
+    /// Hauteur logique en pixels, selon la rotation
+    pub fn hauteur(&self) -> usize {
+        match self.rotation.echange_dimensions() {
+            true => self.largeur,
+            false => self.hauteur,
+        }
+    }
+
+    /// Largeur physique des plans en pixels
+    pub fn largeur_physique(&self) -> usize {
+        self.largeur
+    }
+
+    /// Hauteur physique des plans en pixels
+    pub fn hauteur_physique(&self) -> usize {
+        self.hauteur
+    }
+
+    /// Rotation de l'image
+    pub fn rotation(&self) -> Rotation {
+        self.rotation
+    }
+
+    /// Définir la rotation de l'image. Le contenu des plans n'est pas modifié.
+    pub fn definir_rotation(&mut self, rotation: Rotation) {
+        self.rotation = rotation;
+    }
+
+    /// Indiquer si l'image est retournée horizontalement
+    pub fn miroir(&self) -> bool {
+        self.miroir
+    }
+
+    /// Retourner horizontalement l'image. Le contenu des plans n'est pas modifié.
+    pub fn definir_miroir(&mut self, miroir: bool) {
+        self.miroir = miroir;
+    }
+
+    /// Indiquer si les coordonnées logiques sont les coordonnées physiques
+    pub(crate) fn est_orientation_physique(&self) -> bool {
+        self.rotation == Rotation::R0 && !self.miroir
+    }
+
+    /// Coordonnées physiques d'un pixel situé dans l'écran
+    fn coordonnees_physiques(&self, x: usize, y: usize) -> (usize, usize) {
+        let x = match self.miroir {
+            true => self.largeur() - 1 - x,
+            false => x,
+        };
+        match self.rotation {
+            Rotation::R0 => (x, y),
+            Rotation::R90 => (self.largeur - 1 - y, x),
+            Rotation::R180 => (self.largeur - 1 - x, self.hauteur - 1 - y),
+            Rotation::R270 => (y, self.hauteur - 1 - x),
+        }
+    }
+
+    /// Zone physique couverte par une zone logique contenue dans l'écran
+    pub(crate) fn zone_physique(&self, zone: &Zone) -> Zone {
+        let (x1, y1) = self.coordonnees_physiques(zone.x, zone.y);
+        let (x2, y2) =
+            self.coordonnees_physiques(zone.x + zone.largeur - 1, zone.y + zone.hauteur - 1);
+        let (x, y) = (x1.min(x2), y1.min(y2));
+        Zone::new(x, y, x1.max(x2) + 1 - x, y1.max(y2) + 1 - y)
+    }
+
+    /// Plan noir
+    pub fn plan_noir(&self) -> &[u8] {
+        &self.noir
+    }
+
+    /// Plan rouge
+    pub fn plan_rouge(&self) -> &[u8] {
+        &self.rouge
+    }
+
+    /// Remplacer le plan noir par des pixels regroupés par 8 (bit de poids fort à gauche),
+    /// organisés selon les dimensions physiques de l'écran : 0 pour un pixel noir, 1 pour un
+    /// pixel blanc
+    pub fn definir_plan_noir(&mut self, plan: &[u8]) -> Result<(), ErreurEcran> {
+        self.remplacer_plan(Plan::Noir, plan)
+    }
+
+    /// Remplacer le plan rouge par des pixels regroupés par 8 (bit de poids fort à gauche),
+    /// organisés selon les dimensions physiques de l'écran : 1 pour un pixel rouge
+    pub fn definir_plan_rouge(&mut self, plan: &[u8]) -> Result<(), ErreurEcran> {
+        self.remplacer_plan(Plan::Rouge, plan)
+    }
+
+    fn remplacer_plan(&mut self, plan: Plan, octets: &[u8]) -> Result<(), ErreurEcran> {
+        let destination = match plan {
+            Plan::Noir => &mut self.noir,
+            Plan::Rouge => &mut self.rouge,
+        };
+        if octets.len() != destination.len() {
+            return Err(ErreurEcran::TailleImageInvalide {
+                attendue: destination.len(),
+                recue: octets.len(),
+            });
+        }
+        destination.copy_from_slice(octets);
+        Ok(())
+    }
+
+    pub(crate) fn plan_noir_mut(&mut self) -> &mut [u8] {
+        &mut self.noir
+    }
+
+    pub(crate) fn plan_rouge_mut(&mut self) -> &mut [u8] {
+        &mut self.rouge
+    }
+
+    /// Remplir la mémoire tampon avec une couleur
+    pub fn effacer(&mut self, couleur: TriCouleur) {
+        let (noir, rouge) = match couleur {
+            TriCouleur::Blanc => (0xFF, 0x00),
+            TriCouleur::Noir => (0x00, 0x00),
+            TriCouleur::Rouge => (0xFF, 0xFF),
+        };
+        self.noir.fill(noir);
+        self.rouge.fill(rouge);
+    }
+
+    /// Position de l'octet et masque du bit correspondant au pixel en coordonnées logiques
+    fn position(&self, x: usize, y: usize) -> (usize, u8) {
+        let (x, y) = self.coordonnees_physiques(x, y);
+        (y * self.largeur.div_ceil(8) + x / 8, 0x80 >> (x % 8))
+    }
+
+    /// Modifier la couleur d'un pixel. Les pixels hors de l'écran sont ignorés.
+    pub fn definir_pixel(&mut self, x: usize, y: usize, couleur: TriCouleur) {
+        if x >= self.largeur() || y >= self.hauteur() {
+            return;
+        }
+
+        let (indice, masque) = self.position(x, y);
+        match couleur {
+            TriCouleur::Blanc => {
+                self.noir[indice] |= masque;
+                self.rouge[indice] &= !masque;
+            }
+            TriCouleur::Noir => {
+                self.noir[indice] &= !masque;
+                self.rouge[indice] &= !masque;
+            }
+            TriCouleur::Rouge => {
+                self.noir[indice] |= masque;
+                self.rouge[indice] |= masque;
+            }
+        }
+    }
+
+    /// Couleur d'un pixel
+    pub fn pixel(&self, x: usize, y: usize) -> Option<TriCouleur> {
+        if x >= self.largeur() || y >= self.hauteur() {
+            return None;
+        }
+
+        let (indice, masque) = self.position(x, y);
+        if self.rouge[indice] & masque != 0 {
+            Some(TriCouleur::Rouge)
+        } else if self.noir[indice] & masque == 0 {
+            Some(TriCouleur::Noir)
+        } else {
+            Some(TriCouleur::Blanc)
+        }
+    }
+
+    /// Modifier le niveau de gris d'un pixel. Les pixels hors de l'écran sont ignorés.
+    pub fn definir_gris(&mut self, x: usize, y: usize, gris: Gris4) {
+        if x >= self.largeur() || y >= self.hauteur() {
+            return;
+        }
+
+        let (indice, masque) = self.position(x, y);
+        match gris {
+            Gris4::Blanc | Gris4::GrisClair => self.noir[indice] |= masque,
+            Gris4::Noir | Gris4::GrisFonce => self.noir[indice] &= !masque,
+        }
+        match gris {
+            Gris4::GrisClair | Gris4::GrisFonce => self.rouge[indice] |= masque,
+            Gris4::Blanc | Gris4::Noir => self.rouge[indice] &= !masque,
+        }
+    }
+
+    /// Niveau de gris d'un pixel
+    pub fn gris(&self, x: usize, y: usize) -> Option<Gris4> {
+        if x >= self.largeur() || y >= self.hauteur() {
+            return None;
+        }
+
+        let (indice, masque) = self.position(x, y);
+        let gris = match (self.noir[indice] & masque != 0, self.rouge[indice] & masque != 0) {
+            (true, false) => Gris4::Blanc,
+            (true, true) => Gris4::GrisClair,
+            (false, true) => Gris4::GrisFonce,
+            (false, false) => Gris4::Noir,
+        };
+        Some(gris)
+    }
+
+    /// Copier les pixels d'une mémoire tampon de mêmes dimensions logiques, quelle que soit son
+    /// orientation
+    pub(crate) fn copier(&mut self, source: &MemoireTampon) {
+        for y in 0..self.hauteur().min(source.hauteur()) {
+            for x in 0..self.largeur().min(source.largeur()) {
+                if let Some(couleur) = source.pixel(x, y) {
+                    self.definir_pixel(x, y, couleur);
+                }
+            }
+        }
+    }
+
+    /// Extraire les octets des plans noir et rouge couverts par une zone alignée sur 8 pixels
+    pub(crate) fn extraire_zone(&self, zone: &Zone) -> (Vec<u8>, Vec<u8>) {
+        let octets_par_ligne = self.largeur.div_ceil(8);
+        let debut_ligne = zone.x / 8;
+        let fin_ligne = (zone.x + zone.largeur) / 8;
+        let mut noir = Vec::with_capacity((fin_ligne - debut_ligne) * zone.hauteur);
+        let mut rouge = Vec::with_capacity((fin_ligne - debut_ligne) * zone.hauteur);
+
+        for y in zone.y..zone.y + zone.hauteur {
+            let debut = y * octets_par_ligne;
+            noir.extend_from_slice(&self.noir[debut + debut_ligne..debut + fin_ligne]);
+            rouge.extend_from_slice(&self.rouge[debut + debut_ligne..debut + fin_ligne]);
+        }
+
+        (noir, rouge)
+    }
+
+    /// Ecrire les octets d'un plan couvrant une zone alignée sur 8 pixels.
+    /// Les octets manquants laissent le reste de la zone inchangé.
+    pub(crate) fn ecrire_zone(&mut self, zone: &Zone, plan: Plan, octets: &[u8]) {
+        let octets_par_ligne = self.largeur.div_ceil(8);
+        let debut_ligne = zone.x / 8;
+        let octets_par_ligne_zone = (zone.x + zone.largeur) / 8 - debut_ligne;
+        if octets_par_ligne_zone == 0 || debut_ligne >= octets_par_ligne {
+            return;
+        }
+        let destination = match plan {
+            Plan::Noir => &mut self.noir,
+            Plan::Rouge => &mut self.rouge,
+        };
+
+        let lignes = octets.chunks(octets_par_ligne_zone);
+        for (y, ligne) in (zone.y..cmp::min(zone.y + zone.hauteur, self.hauteur)).zip(lignes) {
+            let longueur = cmp::min(ligne.len(), octets_par_ligne - debut_ligne);
+            let debut = y * octets_par_ligne + debut_ligne;
+            destination[debut..debut + longueur].copy_from_slice(&ligne[..longueur]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Mémoire tampon physique de 16×8 pixels
+    fn orientee(rotation: Rotation, miroir: bool) -> MemoireTampon {
+        let mut memoire_tampon = MemoireTampon::new(16, 8);
+        memoire_tampon.definir_rotation(rotation);
+        memoire_tampon.definir_miroir(miroir);
+        memoire_tampon
+    }
+
+    #[test]
+    fn coordonnees_physiques_rotations() {
+        // Origine, pixel suivant sur la ligne et dernier pixel en coordonnées logiques
+        let attendues = [
+            (Rotation::R0, (16, 8), [(0, 0), (1, 0), (15, 7)]),
+            (Rotation::R90, (8, 16), [(15, 0), (15, 1), (0, 7)]),
+            (Rotation::R180, (16, 8), [(15, 7), (14, 7), (0, 0)]),
+            (Rotation::R270, (8, 16), [(0, 7), (0, 6), (15, 0)]),
+        ];
+        for (rotation, (largeur, hauteur), physiques) in attendues {
+            let memoire_tampon = orientee(rotation, false);
+            let dimensions = (memoire_tampon.largeur(), memoire_tampon.hauteur());
+            assert_eq!(dimensions, (largeur, hauteur));
+            let logiques = [(0, 0), (1, 0), (largeur - 1, hauteur - 1)];
+            for ((x, y), physique) in logiques.into_iter().zip(physiques) {
+                assert_eq!(
+                    memoire_tampon.coordonnees_physiques(x, y),
+                    physique,
+                    "{rotation:?} ({x}, {y})"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn coordonnees_physiques_miroir() {
+        // Le miroir retourne l'image logique horizontalement avant la rotation
+        let memoire_tampon = orientee(Rotation::R0, true);
+        assert_eq!(memoire_tampon.coordonnees_physiques(0, 0), (15, 0));
+        assert_eq!(memoire_tampon.coordonnees_physiques(15, 7), (0, 7));
+
+        let memoire_tampon = orientee(Rotation::R90, true);
+        assert_eq!(memoire_tampon.coordonnees_physiques(0, 0), (15, 7));
+        assert_eq!(memoire_tampon.coordonnees_physiques(7, 15), (0, 0));
+
+        let memoire_tampon = orientee(Rotation::R270, true);
+        assert_eq!(memoire_tampon.coordonnees_physiques(0, 0), (0, 0));
+        assert_eq!(memoire_tampon.coordonnees_physiques(7, 15), (15, 7));
+    }
+
+    #[test]
+    fn pixel_place_selon_rotation() {
+        let mut memoire_tampon = orientee(Rotation::R90, false);
+        memoire_tampon.definir_pixel(1, 0, TriCouleur::Noir);
+        assert_eq!(memoire_tampon.pixel(1, 0), Some(TriCouleur::Noir));
+
+        // Pixel physique (15, 1) : dernier bit du deuxième octet de la deuxième ligne
+        let mut plan_noir = vec![0xFF; 16];
+        plan_noir[3] = 0xFE;
+        assert_eq!(memoire_tampon.plan_noir(), plan_noir);
+        assert_eq!(
+            memoire_tampon.zone_physique(&Zone::new(0, 0, 2, 3)),
+            Zone::new(13, 0, 3, 2)
+        );
+    }
+}
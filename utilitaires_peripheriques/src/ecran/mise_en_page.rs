@@ -0,0 +1,710 @@
+use std::{collections::HashMap, fs, path::Path, path::PathBuf};
+
+use chrono::{Locale, NaiveDateTime};
+use serde::Deserialize;
+
+use crate::ecran::{
+    erreur::ErreurEcran,
+    memoire_tampon::{MemoireTampon, TriCouleur},
+    texte::{Polices, StyleTexte},
+    widget::{
+        self, Calendrier, Colonne, Contexte, Dimension, Grille, Icone, Texte, ValeurCapteur, Widget,
+    },
+    zone::Zone,
+};
+
+/// Texte affiché pour une donnée inconnue
+const VALEUR_INCONNUE: &str = "—";
+
+/// Valeur d'une donnée liée à la mise en page
+#[derive(Clone, Debug, PartialEq)]
+pub enum Valeur {
+    /// Texte affiché tel quel
+    Texte(String),
+    /// Nombre, dont la précision peut être choisie dans le modèle (`{nom:.1}`)
+    Nombre(f64),
+}
+
+impl From<f64> for Valeur {
+    fn from(valeur: f64) -> Self {
+        Valeur::Nombre(valeur)
+    }
+}
+
+impl From<String> for Valeur {
+    fn from(valeur: String) -> Self {
+        Valeur::Texte(valeur)
+    }
+}
+
+impl From<&str> for Valeur {
+    fn from(valeur: &str) -> Self {
+        Valeur::Texte(valeur.to_string())
+    }
+}
+
+/// Données affichées par une mise en page
+///
+/// Les textes peuvent contenir des références `{nom}` ou `{nom:format}` remplacées lors du
+/// dessin. `{date:format}` affiche la date avec la syntaxe de [`chrono::format::strftime`]
+/// (`%d/%m/%Y` par défaut) et `{heure}` équivaut à `{date:%H:%M}`. `{{` et `}}` affichent
+/// une accolade. Les données inconnues et les dates impossibles à mettre en forme sont
+/// remplacées par « — ».
+#[derive(Clone, Debug)]
+pub struct Donnees {
+    maintenant: NaiveDateTime,
+    valeurs: HashMap<String, Valeur>,
+}
+
+impl Donnees {
+    /// Données sans valeur à une date donnée
+    pub fn new(maintenant: NaiveDateTime) -> Self {
+        Self {
+            maintenant,
+            valeurs: HashMap::new(),
+        }
+    }
+
+    /// Définir une valeur
+    pub fn definir(&mut self, nom: impl Into<String>, valeur: impl Into<Valeur>) {
+        self.valeurs.insert(nom.into(), valeur.into());
+    }
+
+    /// Ajouter une valeur
+    pub fn avec(mut self, nom: impl Into<String>, valeur: impl Into<Valeur>) -> Self {
+        self.definir(nom, valeur);
+        self
+    }
+
+    /// Valeur numérique d'une donnée
+    fn nombre(&self, nom: &str) -> Option<f64> {
+        match self.valeurs.get(nom) {
+            Some(Valeur::Nombre(valeur)) => Some(*valeur),
+            Some(Valeur::Texte(valeur)) => valeur.parse().ok(),
+            None => None,
+        }
+    }
+
+    /// Texte d'une référence `nom` ou `nom:format`
+    fn valeur(&self, reference: &str, locale: Locale) -> String {
+        let (nom, format) = match reference.split_once(':') {
+            Some((nom, format)) => (nom, Some(format)),
+            None => (reference, None),
+        };
+        // Seule la date avec fuseau horaire peut être mise en forme selon la langue
+        let maintenant = self.maintenant.and_utc();
+        match (nom, self.valeurs.get(nom)) {
+            ("date" | "heure", None) => {
+                let defaut = match nom {
+                    "date" => "%d/%m/%Y",
+                    _ => "%H:%M",
+                };
+                widget::ecrire_date(maintenant.format_localized(format.unwrap_or(defaut), locale))
+                    .unwrap_or_else(|| VALEUR_INCONNUE.to_string())
+            }
+            (_, Some(Valeur::Texte(valeur))) => valeur.clone(),
+            (_, Some(Valeur::Nombre(valeur))) => {
+                match format.and_then(|format| format.strip_prefix('.')?.parse().ok()) {
+                    Some(decimales) => format!("{valeur:.decimales$}"),
+                    None => valeur.to_string(),
+                }
+            }
+            (_, None) => {
+                log::warn!("Donnée inconnue : {nom}");
+                VALEUR_INCONNUE.to_string()
+            }
+        }
+    }
+
+    /// Remplacer les références d'un modèle de texte
+    fn remplacer(&self, modele: &str, locale: Locale) -> String {
+        let mut texte = String::with_capacity(modele.len());
+        let mut caracteres = modele.chars().peekable();
+        while let Some(caractere) = caracteres.next() {
+            match caractere {
+                '{' | '}' if caracteres.peek() == Some(&caractere) => {
+                    caracteres.next();
+                    texte.push(caractere);
+                }
+                '{' => {
+                    let reference: String =
+                        caracteres.by_ref().take_while(|caractere| *caractere != '}').collect();
+                    texte.push_str(&self.valeur(&reference, locale));
+                }
+                _ => texte.push(caractere),
+            }
+        }
+        texte
+    }
+}
+
+/// Vérifier que les accolades d'un modèle de texte sont appariées et que les formats de la
+/// date et de l'heure sont valides
+fn verifier_modele(modele: &str) -> Result<(), ErreurEcran> {
+    let erreur = || ErreurEcran::MiseEnPageInvalide(format!("accolades non appariées : {modele}"));
+    let mut reference: Option<String> = None;
+    let mut caracteres = modele.chars().peekable();
+    while let Some(caractere) = caracteres.next() {
+        match (caractere, &mut reference) {
+            ('}', Some(texte)) => {
+                verifier_reference(texte)?;
+                reference = None;
+            }
+            (_, Some(texte)) => texte.push(caractere),
+            ('{' | '}', None) if caracteres.peek() == Some(&caractere) => {
+                caracteres.next();
+            }
+            ('{', None) => reference = Some(String::new()),
+            ('}', None) => return Err(erreur()),
+            _ => {}
+        }
+    }
+    match reference {
+        Some(_) => Err(erreur()),
+        None => Ok(()),
+    }
+}
+
+/// Vérifier la taille et l'interligne d'un style de texte
+fn verifier_style(style: &StyleTexte) -> Result<(), ErreurEcran> {
+    style
+        .verifier()
+        .map_err(|erreur| ErreurEcran::MiseEnPageInvalide(erreur.to_string()))
+}
+
+/// Vérifier le format d'une référence `date:format` ou `heure:format`
+fn verifier_reference(reference: &str) -> Result<(), ErreurEcran> {
+    match reference.split_once(':') {
+        Some(("date" | "heure", format)) => widget::verifier_format_date(format).map_err(|_| {
+            ErreurEcran::MiseEnPageInvalide(format!("format de date invalide : {{{reference}}}"))
+        }),
+        _ => Ok(()),
+    }
+}
+
+/// Description d'un widget (voir [`crate::ecran::widget`])
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum DescriptionWidget {
+    /// Texte pouvant contenir des références aux données
+    Texte {
+        /// Modèle du texte, par exemple `{date:%A}`
+        texte: String,
+        /// Style du texte, voir [`StyleTexte::new`] pour les valeurs par défaut
+        #[serde(default)]
+        style: StyleTexte,
+    },
+    /// Calendrier du mois courant
+    Calendrier {
+        /// Style du texte, voir [`StyleTexte::new`] pour les valeurs par défaut
+        #[serde(default)]
+        style: StyleTexte,
+        /// Couleur du fond du jour courant
+        #[serde(default)]
+        couleur_aujourdhui: Option<TriCouleur>,
+    },
+    /// Valeur numérique d'une donnée avec son libellé et son unité
+    ValeurCapteur {
+        /// Libellé
+        libelle: String,
+        /// Nom de la donnée, par exemple `luminosite_lux`
+        valeur: String,
+        /// Unité
+        #[serde(default)]
+        unite: String,
+        /// Nombre de décimales
+        #[serde(default)]
+        decimales: Option<usize>,
+        /// Style du libellé
+        #[serde(default)]
+        style_libelle: Option<StyleTexte>,
+        /// Style de la valeur
+        #[serde(default)]
+        style_valeur: Option<StyleTexte>,
+    },
+    /// Icône monochrome (voir [`Icone`])
+    Icone {
+        /// Largeur en pixels
+        largeur: usize,
+        /// Hauteur en pixels
+        hauteur: usize,
+        /// Pixels, un bit par pixel, lignes complétées à l'octet
+        pixels: Vec<u8>,
+        /// Couleur
+        #[serde(default)]
+        couleur: Option<TriCouleur>,
+        /// Facteur d'agrandissement
+        #[serde(default)]
+        echelle: Option<usize>,
+    },
+    /// Eléments empilés verticalement
+    Colonne {
+        /// Espacement vertical entre les éléments
+        #[serde(default)]
+        espacement: usize,
+        /// Eléments et leur hauteur
+        enfants: Vec<EnfantColonne>,
+    },
+    /// Eléments disposés dans des cases de mêmes dimensions
+    Grille {
+        /// Nombre de colonnes
+        colonnes: usize,
+        /// Espacement entre les cases
+        #[serde(default)]
+        espacement: usize,
+        /// Eléments, ligne par ligne
+        enfants: Vec<DescriptionWidget>,
+    },
+}
+
+/// Elément d'une colonne
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct EnfantColonne {
+    /// Hauteur de l'élément
+    #[serde(default = "dimension_auto")]
+    pub dimension: Dimension,
+    /// Elément
+    pub widget: DescriptionWidget,
+}
+
+fn dimension_auto() -> Dimension {
+    Dimension::Auto
+}
+
+impl DescriptionWidget {
+    fn verifier(&self) -> Result<(), ErreurEcran> {
+        match self {
+            DescriptionWidget::Texte { texte, style } => {
+                verifier_style(style)?;
+                verifier_modele(texte)
+            }
+            DescriptionWidget::ValeurCapteur {
+                libelle,
+                style_libelle,
+                style_valeur,
+                ..
+            } => {
+                style_libelle
+                    .iter()
+                    .chain(style_valeur)
+                    .try_for_each(verifier_style)?;
+                verifier_modele(libelle)
+            }
+            DescriptionWidget::Icone {
+                largeur,
+                hauteur,
+                pixels,
+                ..
+            } => Icone::new(*largeur, *hauteur, pixels.clone()).map(|_| ()),
+            DescriptionWidget::Colonne { enfants, .. } => enfants
+                .iter()
+                .try_for_each(|enfant| enfant.widget.verifier()),
+            DescriptionWidget::Grille {
+                colonnes, enfants, ..
+            } => {
+                if *colonnes == 0 {
+                    return Err(ErreurEcran::MiseEnPageInvalide(
+                        "grille sans colonne".to_string(),
+                    ));
+                }
+                enfants.iter().try_for_each(DescriptionWidget::verifier)
+            }
+            DescriptionWidget::Calendrier { style, .. } => verifier_style(style),
+        }
+    }
+
+    /// Construire le widget avec les données courantes
+    fn construire(&self, donnees: &Donnees, locale: Locale) -> Result<Box<dyn Widget>, ErreurEcran> {
+        Ok(match self {
+            DescriptionWidget::Texte { texte, style } => {
+                Box::new(Texte::new(donnees.remplacer(texte, locale), *style))
+            }
+            DescriptionWidget::Calendrier {
+                style,
+                couleur_aujourdhui,
+            } => {
+                let mut calendrier = Calendrier::new(*style);
+                if let Some(couleur) = couleur_aujourdhui {
+                    calendrier = calendrier.avec_couleur_aujourdhui(*couleur);
+                }
+                Box::new(calendrier)
+            }
+            DescriptionWidget::ValeurCapteur {
+                libelle,
+                valeur,
+                unite,
+                decimales,
+                style_libelle,
+                style_valeur,
+            } => {
+                let mut widget = ValeurCapteur::new(
+                    donnees.remplacer(libelle, locale),
+                    donnees.nombre(valeur),
+                    unite.clone(),
+                );
+                if let Some(decimales) = decimales {
+                    widget = widget.avec_decimales(*decimales);
+                }
+                if let Some(style_libelle) = style_libelle {
+                    widget = widget.avec_style_libelle(*style_libelle);
+                }
+                if let Some(style_valeur) = style_valeur {
+                    widget = widget.avec_style_valeur(*style_valeur);
+                }
+                Box::new(widget)
+            }
+            DescriptionWidget::Icone {
+                largeur,
+                hauteur,
+                pixels,
+                couleur,
+                echelle,
+            } => {
+                let mut icone = Icone::new(*largeur, *hauteur, pixels.clone())?;
+                if let Some(couleur) = couleur {
+                    icone = icone.avec_couleur(*couleur);
+                }
+                if let Some(echelle) = echelle {
+                    icone = icone.avec_echelle(*echelle);
+                }
+                Box::new(icone)
+            }
+            DescriptionWidget::Colonne {
+                espacement,
+                enfants,
+            } => {
+                let mut colonne = Colonne::new().avec_espacement(*espacement);
+                for enfant in enfants {
+                    colonne = colonne.avec(enfant.widget.construire(donnees, locale)?, enfant.dimension);
+                }
+                Box::new(colonne)
+            }
+            DescriptionWidget::Grille {
+                colonnes,
+                espacement,
+                enfants,
+            } => {
+                let mut grille = Grille::new(*colonnes).avec_espacement(*espacement);
+                for enfant in enfants {
+                    grille = grille.avec(enfant.construire(donnees, locale)?);
+                }
+                Box::new(grille)
+            }
+        })
+    }
+}
+
+/// Région rectangulaire de l'écran contenant un widget
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Region {
+    /// Abscisse du coin supérieur gauche
+    pub x: usize,
+    /// Ordonnée du coin supérieur gauche
+    pub y: usize,
+    /// Largeur
+    pub largeur: usize,
+    /// Hauteur
+    pub hauteur: usize,
+    /// Couleur du fond de la région, inchangé si absent
+    #[serde(default)]
+    pub fond: Option<TriCouleur>,
+    /// Widget dessiné dans la région
+    pub widget: DescriptionWidget,
+}
+
+impl Region {
+    /// Zone de la région
+    pub fn zone(&self) -> Zone {
+        Zone::new(self.x, self.y, self.largeur, self.hauteur)
+    }
+}
+
+/// Polices de caractères de la mise en page
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct DescriptionPolices {
+    /// Fichier de la police principale
+    pub principale: PathBuf,
+    /// Fichiers des polices de repli
+    #[serde(default)]
+    pub repli: Vec<PathBuf>,
+}
+
+impl DescriptionPolices {
+    /// Charger les polices
+    pub fn charger(&self) -> Result<Polices, ErreurEcran> {
+        self.repli
+            .iter()
+            .try_fold(Polices::depuis_fichier(&self.principale)?, |polices, repli| {
+                polices.avec_fichier_repli(repli)
+            })
+    }
+}
+
+/// Mise en page d'un écran décrite dans un fichier TOML ou JSON
+///
+/// Exemple au format TOML :
+///
+/// ```toml
+/// largeur = 800
+/// hauteur = 480
+///
+/// [polices]
+/// principale = "/usr/share/fonts/truetype/dejavu/DejaVuSerif.ttf"
+///
+/// [[regions]]
+/// x = 0
+/// y = 0
+/// largeur = 800
+/// hauteur = 240
+/// widget = { type = "texte", texte = "{date:%A}", style = { taille = 120, couleur = "rouge", alignement = "centre" } }
+///
+/// [[regions]]
+/// x = 0
+/// y = 240
+/// largeur = 800
+/// hauteur = 240
+/// widget = { type = "valeur_capteur", libelle = "Luminosité", valeur = "luminosite_lux", unite = "lux" }
+/// ```
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct MiseEnPage {
+    /// Largeur logique de l'écran attendue
+    #[serde(default)]
+    pub largeur: Option<usize>,
+    /// Hauteur logique de l'écran attendue
+    #[serde(default)]
+    pub hauteur: Option<usize>,
+    /// Langue des dates, `fr_FR` par défaut
+    #[serde(default = "locale_defaut")]
+    pub locale: String,
+    /// Couleur de fond de l'écran, blanc par défaut
+    #[serde(default = "fond_defaut")]
+    pub fond: Option<TriCouleur>,
+    /// Polices de caractères
+    pub polices: DescriptionPolices,
+    /// Régions de l'écran
+    pub regions: Vec<Region>,
+}
+
+fn locale_defaut() -> String {
+    "fr_FR".to_string()
+}
+
+fn fond_defaut() -> Option<TriCouleur> {
+    Some(TriCouleur::Blanc)
+}
+
+impl MiseEnPage {
+    /// Lire une mise en page au format TOML
+    pub fn depuis_toml(texte: &str) -> Result<Self, ErreurEcran> {
+        toml::from_str(texte).map_err(|erreur| ErreurEcran::MiseEnPageInvalide(erreur.to_string()))
+    }
+
+    /// Lire une mise en page au format JSON
+    pub fn depuis_json(texte: &str) -> Result<Self, ErreurEcran> {
+        serde_json::from_str(texte)
+            .map_err(|erreur| ErreurEcran::MiseEnPageInvalide(erreur.to_string()))
+    }
+
+    /// Lire une mise en page depuis un fichier `.toml` ou `.json`
+    pub fn charger(chemin: impl AsRef<Path>) -> Result<Self, ErreurEcran> {
+        let chemin = chemin.as_ref();
+        let texte = fs::read_to_string(chemin)?;
+        match chemin.extension().and_then(|extension| extension.to_str()) {
+            Some("toml") => Self::depuis_toml(&texte),
+            Some("json") => Self::depuis_json(&texte),
+            _ => Err(ErreurEcran::FormatNonPrisEnCharge(chemin.to_path_buf())),
+        }
+    }
+
+    fn locale(&self) -> Result<Locale, ErreurEcran> {
+        Locale::try_from(self.locale.as_str())
+            .map_err(|_| ErreurEcran::MiseEnPageInvalide(format!("langue inconnue : {}", self.locale)))
+    }
+
+    /// Vérifier la mise en page pour un écran de dimensions logiques données : dimensions
+    /// attendues, régions contenues dans l'écran, langue, modèles et styles de texte et icônes
+    pub fn valider(&self, largeur: usize, hauteur: usize) -> Result<(), ErreurEcran> {
+        let attendues = (
+            self.largeur.unwrap_or(largeur),
+            self.hauteur.unwrap_or(hauteur),
+        );
+        if attendues != (largeur, hauteur) {
+            return Err(ErreurEcran::DimensionsInvalides {
+                attendues,
+                recues: (largeur, hauteur),
+            });
+        }
+        self.locale()?;
+        for region in &self.regions {
+            let zone = region.zone();
+            if zone.est_vide() || !zone.est_contenue(largeur, hauteur) {
+                return Err(ErreurEcran::ZoneInvalide(zone));
+            }
+            region.widget.verifier()?;
+        }
+        Ok(())
+    }
+
+    /// Dessiner la mise en page dans la mémoire tampon après l'avoir validée
+    pub fn dessiner(
+        &self,
+        polices: &Polices,
+        donnees: &Donnees,
+        memoire_tampon: &mut MemoireTampon,
+    ) -> Result<(), ErreurEcran> {
+        self.valider(memoire_tampon.largeur(), memoire_tampon.hauteur())?;
+        let locale = self.locale()?;
+        let contexte = Contexte::new(polices, donnees.maintenant);
+
+        if let Some(fond) = self.fond {
+            memoire_tampon.effacer(fond);
+        }
+        for region in &self.regions {
+            let zone = region.zone();
+            if let Some(fond) = region.fond {
+                widget::remplir(memoire_tampon, zone, fond);
+            }
+            region
+                .widget
+                .construire(donnees, locale)?
+                .dessiner(&contexte, memoire_tampon, zone);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+
+    const MISE_EN_PAGE_TOML: &str = r#"
+largeur = 800
+hauteur = 480
+
+[polices]
+principale = "DejaVuSansMono.ttf"
+
+[[regions]]
+x = 0
+y = 0
+largeur = 800
+hauteur = 240
+widget = { type = "texte", texte = "{date:%A %e %B} à {heure}", style = { taille = 120 } }
+
+[[regions]]
+x = 0
+y = 240
+largeur = 800
+hauteur = 240
+widget = { type = "valeur_capteur", libelle = "Luminosité", valeur = "luminosite_lux" }
+"#;
+
+    const MISE_EN_PAGE_JSON: &str = r#"{
+        "polices": { "principale": "DejaVuSansMono.ttf" },
+        "regions": [{
+            "x": 0, "y": 0, "largeur": 400, "hauteur": 300,
+            "widget": { "type": "colonne", "enfants": [
+                { "widget": { "type": "texte", "texte": "{heure:%H h %M}" } },
+                { "widget": { "type": "texte", "texte": "{temperature:.1} °C" } }
+            ] }
+        }]
+    }"#;
+
+    fn donnees() -> Donnees {
+        let maintenant = NaiveDate::from_ymd_opt(2024, 2, 29)
+            .unwrap()
+            .and_hms_opt(7, 5, 0)
+            .unwrap();
+        Donnees::new(maintenant).avec("temperature", 21.46)
+    }
+
+    fn erreur_mise_en_page(resultat: Result<(), ErreurEcran>) -> String {
+        match resultat {
+            Err(ErreurEcran::MiseEnPageInvalide(message)) => message,
+            resultat => panic!("erreur de mise en page attendue : {resultat:?}"),
+        }
+    }
+
+    #[test]
+    fn lecture_toml() {
+        let mise_en_page = MiseEnPage::depuis_toml(MISE_EN_PAGE_TOML).unwrap();
+        assert_eq!(mise_en_page.regions.len(), 2);
+        assert_eq!(mise_en_page.locale, "fr_FR");
+        assert_eq!(mise_en_page.fond, Some(TriCouleur::Blanc));
+        assert!(mise_en_page.valider(800, 480).is_ok());
+        assert!(matches!(
+            mise_en_page.valider(480, 800),
+            Err(ErreurEcran::DimensionsInvalides { .. })
+        ));
+    }
+
+    #[test]
+    fn lecture_json() {
+        let mise_en_page = MiseEnPage::depuis_json(MISE_EN_PAGE_JSON).unwrap();
+        assert_eq!(mise_en_page.largeur, None);
+        assert!(mise_en_page.valider(400, 300).is_ok());
+        assert!(matches!(
+            mise_en_page.valider(300, 400),
+            Err(ErreurEcran::ZoneInvalide(_))
+        ));
+        assert!(MiseEnPage::depuis_json(r#"{ "regions": [] }"#).is_err());
+    }
+
+    #[test]
+    fn format_de_date_invalide() {
+        let toml = MISE_EN_PAGE_TOML.replace("{heure}", "{heure:%Q}");
+        let mise_en_page = MiseEnPage::depuis_toml(&toml).unwrap();
+        let message = erreur_mise_en_page(mise_en_page.valider(800, 480));
+        assert!(message.contains("{heure:%Q}"), "{message}");
+
+        let json = MISE_EN_PAGE_JSON.replace("%H h %M", "%H h %");
+        let mise_en_page = MiseEnPage::depuis_json(&json).unwrap();
+        let message = erreur_mise_en_page(mise_en_page.valider(400, 300));
+        assert!(message.contains("{heure:%H h %}"), "{message}");
+    }
+
+    #[test]
+    fn interligne_invalide() {
+        for interligne in ["0", "-1.5", "nan", "inf"] {
+            let toml = MISE_EN_PAGE_TOML.replace(
+                "style = { taille = 120 }",
+                &format!("style = {{ taille = 120, interligne = {interligne} }}"),
+            );
+            let mise_en_page = MiseEnPage::depuis_toml(&toml).unwrap();
+            let message = erreur_mise_en_page(mise_en_page.valider(800, 480));
+            assert!(message.contains("interligne"), "{message}");
+        }
+    }
+
+    #[test]
+    fn accolades_non_appariees() {
+        for modele in ["{date", "date}", "{{date}", "{a}}"] {
+            assert!(verifier_modele(modele).is_err(), "{modele}");
+        }
+        for modele in ["", "{{date}}", "{a}{b:.2} {{", "{date:%d/%m}"] {
+            assert!(verifier_modele(modele).is_ok(), "{modele}");
+        }
+    }
+
+    #[test]
+    fn remplacement_des_references() {
+        let donnees = donnees();
+        let remplacer = |modele| donnees.remplacer(modele, Locale::fr_FR);
+        assert_eq!(
+            remplacer("{date:%A %e %B} à {heure}"),
+            "jeudi 29 février à 07:05"
+        );
+        assert_eq!(remplacer("{date}"), "29/02/2024");
+        assert_eq!(remplacer("{temperature:.1} °C"), "21.5 °C");
+        assert_eq!(remplacer("{{{temperature}}}"), "{21.46}");
+        assert_eq!(remplacer("{inconnue}"), VALEUR_INCONNUE);
+        // Un format invalide ne provoque pas de panique lors du dessin
+        assert_eq!(remplacer("{date:%Q}"), VALEUR_INCONNUE);
+    }
+}
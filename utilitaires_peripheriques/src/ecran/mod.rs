@@ -3,7 +3,59 @@
 //#![doc(html_root_url = "https://docs.rs/ssd1351/0.2.0")]
 #![warn(missing_docs, unused_qualifications)]
 
+/// Etat d'alimentation de l'écran
+pub mod alimentation;
+/// Codes QR et codes-barres
+#[cfg(feature = "code_barre")]
+pub mod code_barre;
+/// Conversion d'images RGB vers les couleurs de l'écran
+pub mod conversion;
+/// Diagnostic de l'écran avec des motifs de test
+pub mod diagnostic;
 /// Liste des méthodes d'affichage de l'écran
+#[allow(clippy::module_inception)]
 pub mod ecran;
+/// Erreurs de l'écran
+pub mod erreur;
+/// Enregistrement de la dernière image affichée
+pub mod image_enregistree;
 /// Liste des commandes de l'écran
 pub mod instruction;
+/// Mémoire tampon de l'écran
+pub mod memoire_tampon;
+/// Graphes de séries de mesures
+#[cfg(feature = "rendu")]
+pub mod graphe;
+/// Dessin dans la mémoire tampon avec embedded-graphics
+#[cfg(feature = "graphique")]
+pub mod graphique;
+/// Mises en page décrites dans des fichiers TOML ou JSON
+#[cfg(feature = "mise_en_page")]
+pub mod mise_en_page;
+/// Modèles d'écrans pris en charge
+pub mod modele;
+/// Abstraction de la liaison SPI et des broches utilisées par l'écran
+pub mod materiel;
+/// Contrôleur de l'écran simulé
+pub mod simulation;
+/// Rendu de documents SVG
+#[cfg(feature = "svg")]
+pub mod svg;
+/// Ecran virtuel enregistrant l'image affichée dans un fichier
+pub mod virtuel;
+/// Mise en page de texte avec rusttype
+#[cfg(feature = "rendu")]
+pub mod texte;
+/// Tables de formes d'onde de l'écran
+pub mod table_forme;
+/// Rotation de l'écran
+pub mod rotation;
+/// Contrôle de la température de l'écran
+pub mod temperature;
+/// Zones de l'écran
+pub mod zone;
+/// Widgets composant un tableau de bord
+#[cfg(feature = "rendu")]
+pub mod widget;
+/// Planification des rafraîchissements et nettoyage de l'écran
+pub mod planificateur;
@@ -0,0 +1,127 @@
+use crate::ecran::instruction::Instruction;
+
+/// Ecran 7.5" noir, blanc et rouge (B V2)
+pub mod wepd7in5b_v2;
+/// Ecran 7.5" noir et blanc (V2)
+pub mod wepd7in5_v2;
+/// Ecran 4.2" noir, blanc et rouge (B)
+pub mod wepd4in2b;
+
+pub use wepd4in2b::Modele4In2B;
+pub use wepd7in5_v2::Modele7In5V2;
+pub use wepd7in5b_v2::Modele7In5BV2;
+
+/// Etape d'une séquence de commandes envoyée au contrôleur de l'écran
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Etape {
+    /// Envoyer une instruction suivie de ses données
+    Instruction(Instruction, &'static [u8]),
+    /// Attendre une durée en millisecondes
+    Attente(u64),
+    /// Attendre que le contrôleur de l'écran soit disponible
+    AttenteDisponibilite,
+}
+
+/// Plans de couleur affichés par l'écran
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PlansCouleur {
+    /// Noir et blanc
+    Noir,
+    /// Noir, blanc et rouge
+    NoirRouge,
+}
+
+/// Plan de la mémoire tampon
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Plan {
+    /// Plan noir (0 pour un pixel noir)
+    Noir,
+    /// Plan rouge (1 pour un pixel rouge)
+    Rouge,
+}
+
+/// Transmission d'un plan de la mémoire tampon au contrôleur de l'écran
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct TransmissionPlan {
+    /// Instruction précédant les données du plan
+    pub instruction: Instruction,
+    /// Plan transmis
+    pub plan: Plan,
+    /// Inverser les bits du plan avant la transmission
+    pub inverse: bool,
+    /// Combiner les bits du plan avec ceux du plan noir par un ou exclusif avant la transmission
+    pub ou_exclusif_noir: bool,
+}
+
+/// Transmission en noir et blanc : ancienne image puis nouvelle image (1 pour un pixel noir)
+pub const TRANSMISSION_NOIR_BLANC: &[TransmissionPlan] = &[
+    TransmissionPlan {
+        instruction: Instruction::DataStartTransmission1,
+        plan: Plan::Noir,
+        inverse: false,
+        ou_exclusif_noir: false,
+    },
+    TransmissionPlan {
+        instruction: Instruction::DataStartTransmission2,
+        plan: Plan::Noir,
+        inverse: true,
+        ou_exclusif_noir: false,
+    },
+];
+
+/// Transmission en 4 niveaux de gris : les bits des deux plans choisissent ensemble la forme
+/// d'onde de chaque pixel (voir [`crate::ecran::table_forme::TableForme::gris_4_niveaux`])
+///
+/// Comme `display_4Gray` de Waveshare pour le 7.5" V2 (epd7in5_V2.py), les bits transmis
+/// (DTM1, DTM2) valent (0, 0) pour le blanc, (0, 1) pour le gris clair, (1, 0) pour le gris
+/// foncé et (1, 1) pour le noir.
+pub const TRANSMISSION_GRIS_4: &[TransmissionPlan] = &[
+    TransmissionPlan {
+        instruction: Instruction::DataStartTransmission1,
+        plan: Plan::Noir,
+        inverse: true,
+        ou_exclusif_noir: false,
+    },
+    TransmissionPlan {
+        instruction: Instruction::DataStartTransmission2,
+        plan: Plan::Rouge,
+        inverse: true,
+        ou_exclusif_noir: true,
+    },
+];
+
+/// Description d'un modèle d'écran à encre électronique Waveshare
+///
+/// Les modèles partagent la liaison SPI, la réinitialisation matérielle et l'attente de la
+/// broche BUSY. Chaque modèle décrit ses dimensions, ses plans de couleur et les séquences de
+/// commandes propres à son contrôleur.
+pub trait ModeleEcran {
+    /// Nom du modèle
+    const NOM: &'static str;
+    /// Largeur en pixels
+    const LARGEUR: usize;
+    /// Hauteur en pixels
+    const HAUTEUR: usize;
+    /// Plans de couleur affichés
+    const PLANS_COULEUR: PlansCouleur;
+    /// Niveau de la broche BUSY lorsque le contrôleur est occupé (vrai pour le niveau bas)
+    const OCCUPE_NIVEAU_BAS: bool;
+    /// Séquence d'initialisation envoyée après la réinitialisation matérielle
+    /// Elle contient le réglage du panneau (`PanelSetting`), modifié lors du chargement d'une
+    /// [`crate::ecran::table_forme::TableForme`].
+    const INITIALISATION: &'static [Etape];
+    /// Transmission des plans de la mémoire tampon
+    const TRANSMISSION: &'static [TransmissionPlan];
+    /// Séquence de rafraîchissement envoyée après la transmission des plans
+    const RAFRAICHISSEMENT: &'static [Etape];
+    /// Séquence de mise hors tension et de mise en veille profonde
+    const EXTINCTION: &'static [Etape];
+    /// Rafraîchissement partiel pris en charge avec la fenêtre du contrôleur UC8179
+    /// (`PartialIn`, `PartialWindow` sur 9 octets, `PartialOut`). Sinon, les mises à jour
+    /// d'une zone rafraîchissent tout l'écran.
+    const FENETRE_PARTIELLE: bool = false;
+    /// Température minimale de fonctionnement en °C
+    const TEMPERATURE_MINIMALE: f32 = 0.;
+    /// Température maximale de fonctionnement en °C
+    const TEMPERATURE_MAXIMALE: f32 = 50.;
+}
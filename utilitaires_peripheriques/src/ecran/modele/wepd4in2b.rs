@@ -0,0 +1,53 @@
+use crate::ecran::{
+    instruction::Instruction,
+    modele::{Etape, ModeleEcran, Plan, PlansCouleur, TransmissionPlan},
+};
+
+/// Ecran à encre électronique noir, blanc et rouge - 4.2inch E-Ink display (B)
+/// Modèle : [`400×300, 4.2inch E-Ink display HAT for Raspberry Pi, three-color`](https://www.waveshare.com/4.2inch-e-paper-hat-b.htm)
+/// Implémentation python officielle : [epd4in2b.py](https://github.com/waveshare/e-Paper/blob/master/RaspberryPi_JetsonNano/python/lib/waveshare_epd/epd4in2b.py)
+pub struct Modele4In2B;
+
+impl ModeleEcran for Modele4In2B {
+    const NOM: &'static str = "4in2b";
+    const LARGEUR: usize = 400;
+    const HAUTEUR: usize = 300;
+    const PLANS_COULEUR: PlansCouleur = PlansCouleur::NoirRouge;
+    const OCCUPE_NIVEAU_BAS: bool = true;
+
+    const INITIALISATION: &'static [Etape] = &[
+        Etape::Instruction(Instruction::BoosterSoftStart, &[0x17, 0x17, 0x17]),
+        Etape::Instruction(Instruction::PowerOn, &[]),
+        Etape::AttenteDisponibilite,
+        Etape::Instruction(Instruction::PanelSetting, &[0x0F]),
+    ];
+
+    // Plan rouge : 0 pour un pixel rouge
+    const TRANSMISSION: &'static [TransmissionPlan] = &[
+        TransmissionPlan {
+            instruction: Instruction::DataStartTransmission1,
+            plan: Plan::Noir,
+            inverse: false,
+            ou_exclusif_noir: false,
+        },
+        TransmissionPlan {
+            instruction: Instruction::DataStartTransmission2,
+            plan: Plan::Rouge,
+            inverse: true,
+            ou_exclusif_noir: false,
+        },
+    ];
+
+    const RAFRAICHISSEMENT: &'static [Etape] = &[
+        Etape::Instruction(Instruction::DisplayRefresh, &[]),
+        Etape::Attente(100),
+        Etape::AttenteDisponibilite,
+    ];
+
+    const EXTINCTION: &'static [Etape] = &[
+        Etape::Instruction(Instruction::VcomAndDataIntervalSetting, &[0xF7]),
+        Etape::Instruction(Instruction::PowerOff, &[]),
+        Etape::AttenteDisponibilite,
+        Etape::Instruction(Instruction::DeepSleep, &[0xA5]),
+    ];
+}
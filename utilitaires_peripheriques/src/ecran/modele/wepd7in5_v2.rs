@@ -0,0 +1,45 @@
+use crate::ecran::{
+    instruction::Instruction,
+    modele::{Etape, ModeleEcran, PlansCouleur, TransmissionPlan, TRANSMISSION_NOIR_BLANC},
+};
+
+/// Ecran à encre électronique noir et blanc - 7.5inch E-Ink display V2
+/// Modèle : [`800×480, 7.5inch E-Ink display HAT for Raspberry Pi`](https://www.waveshare.com/7.5inch-e-paper-hat.htm)
+/// Implémentation python officielle : [epd7in5_V2.py](https://github.com/waveshare/e-Paper/blob/master/RaspberryPi_JetsonNano/python/lib/waveshare_epd/epd7in5_V2.py)
+pub struct Modele7In5V2;
+
+impl ModeleEcran for Modele7In5V2 {
+    const NOM: &'static str = "7in5_V2";
+    const LARGEUR: usize = 800;
+    const HAUTEUR: usize = 480;
+    const PLANS_COULEUR: PlansCouleur = PlansCouleur::Noir;
+    const OCCUPE_NIVEAU_BAS: bool = true;
+    const FENETRE_PARTIELLE: bool = true;
+
+    const INITIALISATION: &'static [Etape] = &[
+        Etape::Instruction(Instruction::PowerSetting, &[0x07, 0x07, 0x3f, 0x3f]),
+        Etape::Instruction(Instruction::BoosterSoftStart, &[0x17, 0x17, 0x28, 0x17]),
+        Etape::Instruction(Instruction::PowerOn, &[]),
+        Etape::Attente(100),
+        Etape::AttenteDisponibilite,
+        Etape::Instruction(Instruction::PanelSetting, &[0x1F]),
+        Etape::Instruction(Instruction::Tres, &[0x03, 0x20, 0x01, 0xE0]),
+        Etape::Instruction(Instruction::Unknown1, &[0x00]),
+        Etape::Instruction(Instruction::VcomAndDataIntervalSetting, &[0x10, 0x07]),
+        Etape::Instruction(Instruction::TconSetting, &[0x22]),
+    ];
+
+    const TRANSMISSION: &'static [TransmissionPlan] = TRANSMISSION_NOIR_BLANC;
+
+    const RAFRAICHISSEMENT: &'static [Etape] = &[
+        Etape::Instruction(Instruction::DisplayRefresh, &[]),
+        Etape::Attente(100),
+        Etape::AttenteDisponibilite,
+    ];
+
+    const EXTINCTION: &'static [Etape] = &[
+        Etape::Instruction(Instruction::PowerOff, &[]),
+        Etape::AttenteDisponibilite,
+        Etape::Instruction(Instruction::DeepSleep, &[0xA5]),
+    ];
+}
@@ -0,0 +1,288 @@
+use rppal::{
+    gpio::{InputPin, OutputPin},
+    spi::Spi,
+};
+use tokio::time::{sleep_until, Duration, Instant};
+
+use crate::ecran::{
+    ecran::{Ecran, MiseAJour},
+    erreur::ErreurEcran,
+    materiel::{BrocheEntree, BrocheSortie, LiaisonSpi},
+    memoire_tampon::TriCouleur,
+    modele::{ModeleEcran, PlansCouleur},
+};
+
+/// Intervalle minimal entre deux rafraîchissements recommandé par Waveshare
+pub const INTERVALLE_MINIMAL_DEFAUT: Duration = Duration::from_secs(180);
+
+/// Cycle de nettoyage de l'écran
+///
+/// Les couleurs de la séquence sont affichées successivement sur tout l'écran avant l'image,
+/// afin d'éviter la rémanence des images précédentes. Comme la séquence d'effacement de
+/// Waveshare, ces rafraîchissements s'enchaînent sans respecter l'intervalle minimal.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Nettoyage {
+    /// Couleurs affichées successivement. Le rouge est ignoré par les écrans noir et blanc.
+    pub sequence: Vec<TriCouleur>,
+    /// Nettoyer l'écran après ce nombre de rafraîchissements
+    pub nombre_rafraichissements: Option<usize>,
+    /// Nettoyer l'écran après cette durée, par exemple une fois par jour
+    pub intervalle: Option<Duration>,
+}
+
+impl Default for Nettoyage {
+    /// Blanc, noir puis rouge tous les 50 rafraîchissements ou une fois par jour
+    fn default() -> Self {
+        Self {
+            sequence: vec![TriCouleur::Blanc, TriCouleur::Noir, TriCouleur::Rouge],
+            nombre_rafraichissements: Some(50),
+            intervalle: Some(Duration::from_secs(24 * 60 * 60)),
+        }
+    }
+}
+
+/// Résultat d'une demande de mise à jour planifiée
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Planification {
+    /// La mise à jour a été effectuée, ou l'image était inchangée
+    Effectuee(MiseAJour),
+    /// Le dernier rafraîchissement est trop récent : la mémoire tampon sera affichée après ce
+    /// délai par [`Planificateur::traiter_attente`]
+    Differee(Duration),
+}
+
+/// Planification des rafraîchissements de l'écran
+///
+/// Limite la fréquence des rafraîchissements, regroupe les demandes trop rapprochées en
+/// affichant uniquement la dernière image et nettoie périodiquement l'écran.
+pub struct Planificateur<M, S = Spi, O = OutputPin, I = InputPin> {
+    ecran: Ecran<M, S, O, I>,
+    intervalle_minimal: Duration,
+    nettoyage: Option<Nettoyage>,
+    dernier_rafraichissement: Option<Instant>,
+    dernier_nettoyage: Instant,
+    rafraichissements_depuis_nettoyage: usize,
+    en_attente: bool,
+}
+
+impl<M: ModeleEcran, S: LiaisonSpi, O: BrocheSortie, I: BrocheEntree> Planificateur<M, S, O, I> {
+    /// Planification des rafraîchissements de l'écran, au plus un toutes les 180 s et sans
+    /// nettoyage
+    pub fn new(ecran: Ecran<M, S, O, I>) -> Self {
+        Self {
+            ecran,
+            intervalle_minimal: INTERVALLE_MINIMAL_DEFAUT,
+            nettoyage: None,
+            dernier_rafraichissement: None,
+            dernier_nettoyage: Instant::now(),
+            rafraichissements_depuis_nettoyage: 0,
+            en_attente: false,
+        }
+    }
+
+    /// Ecran planifié
+    pub fn ecran(&self) -> &Ecran<M, S, O, I> {
+        &self.ecran
+    }
+
+    /// Ecran planifié modifiable. Les mises à jour effectuées directement sur l'écran ne sont
+    /// pas limitées.
+    pub fn ecran_mut(&mut self) -> &mut Ecran<M, S, O, I> {
+        &mut self.ecran
+    }
+
+    /// Récupérer l'écran planifié
+    pub fn into_ecran(self) -> Ecran<M, S, O, I> {
+        self.ecran
+    }
+
+    /// Définir l'intervalle minimal entre deux rafraîchissements
+    pub fn definir_intervalle_minimal(&mut self, intervalle_minimal: Duration) {
+        self.intervalle_minimal = intervalle_minimal;
+    }
+
+    /// Définir le cycle de nettoyage de l'écran, ou ne jamais nettoyer l'écran (`None`)
+    pub fn definir_nettoyage(&mut self, nettoyage: Option<Nettoyage>) {
+        self.nettoyage = nettoyage;
+    }
+
+    /// Indiquer si une mise à jour différée est en attente
+    pub fn est_en_attente(&self) -> bool {
+        self.en_attente
+    }
+
+    /// Délai avant le prochain rafraîchissement autorisé
+    pub fn delai_restant(&self) -> Duration {
+        match self.dernier_rafraichissement {
+            Some(dernier_rafraichissement) => {
+                (dernier_rafraichissement + self.intervalle_minimal)
+                    .saturating_duration_since(Instant::now())
+            }
+            None => Duration::ZERO,
+        }
+    }
+
+    /// Demander la mise à jour de l'écran avec le contenu de la mémoire tampon.
+    /// Si le dernier rafraîchissement est trop récent, la mise à jour est différée : la mémoire
+    /// tampon pourra encore être modifiée et seule la dernière image sera affichée.
+    pub async fn mettre_a_jour(&mut self) -> Result<Planification, ErreurEcran> {
+        let delai_restant = self.delai_restant();
+        if !delai_restant.is_zero() {
+            log::debug!("Mise à jour différée de {delai_restant:?}");
+            self.en_attente = true;
+            return Ok(Planification::Differee(delai_restant));
+        }
+
+        Ok(Planification::Effectuee(self.effectuer().await?))
+    }
+
+    /// Attendre le prochain rafraîchissement autorisé et effectuer la mise à jour différée.
+    /// Renvoie `None` si aucune mise à jour n'est en attente.
+    pub async fn traiter_attente(&mut self) -> Result<Option<MiseAJour>, ErreurEcran> {
+        if !self.en_attente {
+            return Ok(None);
+        }
+        sleep_until(Instant::now() + self.delai_restant()).await;
+        Ok(Some(self.effectuer().await?))
+    }
+
+    /// Nettoyer l'écran si nécessaire puis le mettre à jour
+    async fn effectuer(&mut self) -> Result<MiseAJour, ErreurEcran> {
+        self.en_attente = false;
+        if self.est_nettoyage_necessaire() {
+            self.nettoyer().await?;
+        }
+
+        let mise_a_jour = self.ecran.mettre_a_jour().await?;
+        if mise_a_jour == MiseAJour::Effectuee {
+            self.dernier_rafraichissement = Some(Instant::now());
+            self.rafraichissements_depuis_nettoyage += 1;
+        }
+        Ok(mise_a_jour)
+    }
+
+    /// Indiquer si le cycle de nettoyage doit être effectué
+    fn est_nettoyage_necessaire(&self) -> bool {
+        let Some(nettoyage) = &self.nettoyage else {
+            return false;
+        };
+        let nombre_atteint = nettoyage
+            .nombre_rafraichissements
+            .is_some_and(|nombre| self.rafraichissements_depuis_nettoyage >= nombre);
+        let intervalle_atteint = nettoyage
+            .intervalle
+            .is_some_and(|intervalle| self.dernier_nettoyage.elapsed() >= intervalle);
+        nombre_atteint || intervalle_atteint
+    }
+
+    /// Afficher successivement les couleurs du cycle de nettoyage. La mémoire tampon est
+    /// restaurée ensuite et l'image est affichée lors de la mise à jour suivante.
+    /// Les rafraîchissements du cycle ne sont pas limités par l'intervalle minimal, qui est
+    /// décompté à partir de la fin du nettoyage. Lorsque le nettoyage est déclenché par une mise
+    /// à jour, l'image est affichée immédiatement après.
+    pub async fn nettoyer(&mut self) -> Result<(), ErreurEcran> {
+        let sequence = self
+            .nettoyage
+            .as_ref()
+            .map(|nettoyage| nettoyage.sequence.clone())
+            .unwrap_or_else(|| Nettoyage::default().sequence);
+
+        log::info!("Nettoyage de l'écran");
+        let memoire_tampon = self.ecran.memoire_tampon().clone();
+        for couleur in sequence {
+            if couleur == TriCouleur::Rouge && M::PLANS_COULEUR == PlansCouleur::Noir {
+                continue;
+            }
+            self.ecran.memoire_tampon_mut().effacer(couleur);
+            if let Err(erreur) = self.ecran.forcer_mise_a_jour().await {
+                *self.ecran.memoire_tampon_mut() = memoire_tampon;
+                return Err(erreur);
+            }
+        }
+        *self.ecran.memoire_tampon_mut() = memoire_tampon;
+
+        self.dernier_nettoyage = Instant::now();
+        self.dernier_rafraichissement = Some(Instant::now());
+        self.rafraichissements_depuis_nettoyage = 0;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecran::{
+        instruction::Instruction, modele::Modele7In5V2, simulation::ControleurSimule,
+    };
+
+    /// Nombre de rafraîchissements reçus par le contrôleur depuis le dernier effacement du
+    /// journal
+    fn rafraichissements(controleur: &ControleurSimule) -> usize {
+        controleur
+            .instructions()
+            .iter()
+            .filter(|instruction| **instruction == Instruction::DisplayRefresh)
+            .count()
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn nettoyage_exempte_de_l_intervalle_minimal() {
+        let controleur = ControleurSimule::new();
+        let mut planificateur = Planificateur::new(controleur.ecran());
+        planificateur.definir_nettoyage(Some(Nettoyage {
+            nombre_rafraichissements: Some(1),
+            intervalle: None,
+            ..Nettoyage::default()
+        }));
+
+        let planification = planificateur.mettre_a_jour().await.unwrap();
+        assert_eq!(
+            planification,
+            Planification::Effectuee(MiseAJour::Effectuee)
+        );
+        assert_eq!(rafraichissements(&controleur), 1);
+
+        // Le nettoyage (blanc, noir, rouge) puis l'image s'enchaînent sans attente
+        controleur.effacer_journal();
+        planificateur
+            .ecran_mut()
+            .definir_pixel(0, 0, TriCouleur::Noir)
+            .unwrap();
+        sleep_until(Instant::now() + INTERVALLE_MINIMAL_DEFAUT).await;
+        let debut = Instant::now();
+        let planification = planificateur.mettre_a_jour().await.unwrap();
+        assert_eq!(
+            planification,
+            Planification::Effectuee(MiseAJour::Effectuee)
+        );
+        assert_eq!(rafraichissements(&controleur), 4);
+        assert!(debut.elapsed() < INTERVALLE_MINIMAL_DEFAUT);
+        assert_eq!(
+            planificateur.ecran().image_affichee(),
+            Some(planificateur.ecran().memoire_tampon())
+        );
+
+        // L'intervalle minimal est décompté à partir du dernier rafraîchissement
+        planificateur
+            .ecran_mut()
+            .definir_pixel(1, 0, TriCouleur::Noir)
+            .unwrap();
+        assert!(matches!(
+            planificateur.mettre_a_jour().await.unwrap(),
+            Planification::Differee(_)
+        ));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn nettoyage_sans_rouge() {
+        let controleur = ControleurSimule::new();
+        let mut planificateur = Planificateur::new(controleur.ecran_modele::<Modele7In5V2>());
+        planificateur.mettre_a_jour().await.unwrap();
+        controleur.effacer_journal();
+
+        // Nettoyage explicite immédiatement après une mise à jour, le rouge est ignoré
+        planificateur.nettoyer().await.unwrap();
+        assert_eq!(rafraichissements(&controleur), 2);
+        assert!(planificateur.delai_restant() > Duration::ZERO);
+    }
+}
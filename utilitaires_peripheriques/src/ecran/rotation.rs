@@ -0,0 +1,20 @@
+/// Rotation de l'image dans le sens horaire
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Rotation {
+    /// Aucune rotation (paysage)
+    #[default]
+    R0,
+    /// Rotation de 90° (portrait)
+    R90,
+    /// Rotation de 180° (paysage inversé)
+    R180,
+    /// Rotation de 270° (portrait inversé)
+    R270,
+}
+
+impl Rotation {
+    /// Indiquer si la rotation échange la largeur et la hauteur
+    pub fn echange_dimensions(&self) -> bool {
+        matches!(self, Rotation::R90 | Rotation::R270)
+    }
+}
@@ -0,0 +1,492 @@
+use std::{
+    convert::Infallible,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use crate::ecran::{
+    ecran::Ecran,
+    instruction::Instruction,
+    materiel::{BrocheEntree, BrocheSortie, LectureSpi, LiaisonSpi},
+    modele::{Modele7In5BV2, ModeleEcran},
+    temperature,
+};
+
+/// Nombre de lectures de la broche BUSY pendant lesquelles le contrôleur reste occupé par défaut
+const DUREE_OCCUPATION_DEFAUT: usize = 2;
+
+/// Température mesurée par le capteur simulé par défaut en °C
+const TEMPERATURE_DEFAUT: f32 = 20.;
+
+/// Ecran relié à un contrôleur simulé
+pub type EcranSimule<M = Modele7In5BV2> =
+    Ecran<M, LiaisonSpiSimulee, BrocheSortieSimulee, BrocheEntreeSimulee>;
+
+/// Commande reçue par le contrôleur simulé
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Commande {
+    /// Code de l'instruction
+    pub code: u8,
+    /// Données reçues après l'instruction
+    pub donnees: Vec<u8>,
+}
+
+impl Commande {
+    /// Instruction correspondant au code reçu
+    pub fn instruction(&self) -> Option<Instruction> {
+        Instruction::determiner(self.code)
+    }
+}
+
+/// Etat interne du contrôleur UC8179 simulé
+struct EtatControleur {
+    dc_haut: bool,
+    cs_haut: bool,
+    rst_haut: bool,
+    commandes: Vec<Commande>,
+    occupe_niveau_bas: bool,
+    duree_occupation: usize,
+    occupation_restante: usize,
+    veille_profonde: bool,
+    nombre_reinitialisations: usize,
+    temperature: f32,
+    reponse: Vec<u8>,
+    miso_cablee: bool,
+    interruptions: bool,
+    rappel_busy: Option<Box<dyn FnMut() + Send>>,
+}
+
+impl EtatControleur {
+    fn new() -> Self {
+        Self {
+            dc_haut: false,
+            cs_haut: true,
+            rst_haut: true,
+            commandes: Vec::new(),
+            occupe_niveau_bas: true,
+            duree_occupation: DUREE_OCCUPATION_DEFAUT,
+            occupation_restante: 0,
+            veille_profonde: false,
+            nombre_reinitialisations: 0,
+            temperature: TEMPERATURE_DEFAUT,
+            reponse: Vec::new(),
+            miso_cablee: true,
+            interruptions: false,
+            rappel_busy: None,
+        }
+    }
+
+    /// Recevoir les octets transmis sur la liaison SPI
+    fn recevoir(&mut self, octets: &[u8]) {
+        if self.cs_haut {
+            log::warn!("Octets reçus alors que le contrôleur n'est pas sélectionné");
+            return;
+        }
+
+        if self.dc_haut {
+            match self.commandes.last_mut() {
+                Some(commande) => commande.donnees.extend_from_slice(octets),
+                None => log::warn!("Données reçues sans instruction préalable"),
+            }
+            self.executer_donnees();
+        } else {
+            for code in octets {
+                self.commandes.push(Commande {
+                    code: *code,
+                    donnees: Vec::new(),
+                });
+                self.executer_instruction(*code);
+            }
+        }
+    }
+
+    /// Simuler l'effet d'une instruction
+    fn executer_instruction(&mut self, code: u8) {
+        if self.veille_profonde {
+            log::warn!("Instruction {code:#04x} reçue en veille profonde");
+            return;
+        }
+
+        self.reponse.clear();
+        match Instruction::determiner(code) {
+            Some(Instruction::TemperatureSensorCalibration) => {
+                self.reponse = temperature::encoder(self.temperature).to_vec();
+                self.occupation_restante = self.duree_occupation;
+            }
+            Some(Instruction::PowerOn)
+            | Some(Instruction::PowerOff)
+            | Some(Instruction::DisplayRefresh) => {
+                self.occupation_restante = self.duree_occupation;
+            }
+            Some(_) => {}
+            None => log::warn!("Instruction inconnue {code:#04x}"),
+        }
+    }
+
+    /// Simuler l'effet des données reçues après une instruction
+    fn executer_donnees(&mut self) {
+        if let Some(commande) = self.commandes.last() {
+            if commande.instruction() == Some(Instruction::DeepSleep) && commande.donnees == [0xA5]
+            {
+                self.veille_profonde = true;
+            }
+        }
+    }
+
+    /// Envoyer la réponse à la dernière instruction sur la liaison SPI
+    fn repondre(&mut self, tampon: &mut [u8]) {
+        if self.cs_haut || !self.dc_haut {
+            log::warn!("Lecture alors que le contrôleur n'est pas prêt à répondre");
+        }
+        if !self.miso_cablee {
+            tampon.fill(0xFF);
+            return;
+        }
+        let longueur = tampon.len().min(self.reponse.len());
+        tampon[..longueur].copy_from_slice(&self.reponse[..longueur]);
+        tampon[longueur..].fill(0);
+        self.reponse.drain(..longueur);
+    }
+
+    /// Simuler un changement de niveau de la broche RST
+    fn changer_rst(&mut self, haut: bool) {
+        if haut && !self.rst_haut {
+            self.veille_profonde = false;
+            self.occupation_restante = 0;
+            self.nombre_reinitialisations += 1;
+        }
+        self.rst_haut = haut;
+    }
+
+    /// Lire le niveau de la broche BUSY (vrai pour le niveau bas)
+    fn lire_busy(&mut self) -> bool {
+        let occupe = if self.occupation_restante > 0 {
+            self.occupation_restante -= 1;
+            true
+        } else {
+            false
+        };
+        occupe == self.occupe_niveau_bas
+    }
+}
+
+/// Contrôleur UC8179 simulé
+///
+/// Enregistre les instructions et les données reçues, et simule la broche BUSY afin de pouvoir
+/// vérifier la séquence de commandes envoyée par le pilote sans Raspberry Pi.
+#[derive(Clone)]
+pub struct ControleurSimule {
+    etat: Arc<Mutex<EtatControleur>>,
+}
+
+impl Default for ControleurSimule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ControleurSimule {
+    /// Contrôleur simulé
+    pub fn new() -> Self {
+        Self {
+            etat: Arc::new(Mutex::new(EtatControleur::new())),
+        }
+    }
+
+    fn etat(&self) -> MutexGuard<'_, EtatControleur> {
+        self.etat.lock().unwrap()
+    }
+
+    /// Ecran 7.5" noir, blanc et rouge relié au contrôleur simulé
+    pub fn ecran(&self) -> EcranSimule {
+        self.ecran_modele()
+    }
+
+    /// Ecran du modèle choisi relié au contrôleur simulé.
+    /// La polarité de la broche BUSY simulée est celle du modèle.
+    pub fn ecran_modele<M: ModeleEcran>(&self) -> EcranSimule<M> {
+        self.etat().occupe_niveau_bas = M::OCCUPE_NIVEAU_BAS;
+        Ecran::avec_materiel(
+            self.liaison_spi(),
+            self.broche(Broche::Dc),
+            self.broche(Broche::Rst),
+            self.broche(Broche::Cs),
+            self.broche_busy(),
+        )
+    }
+
+    /// Liaison SPI reliée au contrôleur simulé
+    pub fn liaison_spi(&self) -> LiaisonSpiSimulee {
+        LiaisonSpiSimulee {
+            etat: self.etat.clone(),
+        }
+    }
+
+    /// Broche de sortie (DC, RST ou CS) reliée au contrôleur simulé
+    pub fn broche(&self, broche: Broche) -> BrocheSortieSimulee {
+        BrocheSortieSimulee {
+            etat: self.etat.clone(),
+            broche,
+        }
+    }
+
+    /// Broche BUSY reliée au contrôleur simulé
+    pub fn broche_busy(&self) -> BrocheEntreeSimulee {
+        BrocheEntreeSimulee {
+            etat: self.etat.clone(),
+        }
+    }
+
+    /// Définir le nombre de lectures de la broche BUSY pendant lesquelles le contrôleur reste
+    /// occupé après une mise sous tension, une mise hors tension, un rafraîchissement ou une
+    /// mesure de la température
+    pub fn definir_duree_occupation(&self, lectures: usize) {
+        self.etat().duree_occupation = lectures;
+    }
+
+    /// Définir la température mesurée par le capteur simulé en °C
+    pub fn definir_temperature(&self, temperature: f32) {
+        self.etat().temperature = temperature;
+    }
+
+    /// Indiquer si la ligne MISO est câblée. Sinon, les lectures renvoient 0xFF (ligne au
+    /// niveau haut)
+    pub fn definir_miso_cablee(&self, cablee: bool) {
+        self.etat().miso_cablee = cablee;
+    }
+
+    /// Indiquer si la broche BUSY simulée prend en charge les interruptions. Sinon, l'écran
+    /// attend la disponibilité du contrôleur par scrutation
+    pub fn definir_interruptions(&self, interruptions: bool) {
+        self.etat().interruptions = interruptions;
+    }
+
+    /// Libérer immédiatement le contrôleur occupé, en signalant le front de la broche BUSY si
+    /// elle est surveillée par interruption
+    pub fn liberer(&self) {
+        let mut etat = self.etat();
+        etat.occupation_restante = 0;
+        if let Some(rappel) = &mut etat.rappel_busy {
+            rappel();
+        }
+    }
+
+    /// Commandes reçues par le contrôleur
+    pub fn commandes(&self) -> Vec<Commande> {
+        self.etat().commandes.clone()
+    }
+
+    /// Instructions reçues par le contrôleur
+    pub fn instructions(&self) -> Vec<Instruction> {
+        self.etat()
+            .commandes
+            .iter()
+            .filter_map(Commande::instruction)
+            .collect()
+    }
+
+    /// Effacer le journal des commandes reçues
+    pub fn effacer_journal(&self) {
+        self.etat().commandes.clear();
+    }
+
+    /// Indiquer si le contrôleur est occupé
+    pub fn est_occupe(&self) -> bool {
+        self.etat().occupation_restante > 0
+    }
+
+    /// Indiquer si le contrôleur est en veille profonde
+    pub fn est_en_veille_profonde(&self) -> bool {
+        self.etat().veille_profonde
+    }
+
+    /// Nombre de réinitialisations matérielles reçues sur la broche RST
+    pub fn nombre_reinitialisations(&self) -> usize {
+        self.etat().nombre_reinitialisations
+    }
+}
+
+/// Broches de sortie du contrôleur
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Broche {
+    /// Sélection données / instruction
+    Dc,
+    /// Réinitialisation
+    Rst,
+    /// Sélection du contrôleur
+    Cs,
+}
+
+/// Liaison SPI reliée au contrôleur simulé
+pub struct LiaisonSpiSimulee {
+    etat: Arc<Mutex<EtatControleur>>,
+}
+
+impl LiaisonSpi for LiaisonSpiSimulee {
+    type Erreur = Infallible;
+
+    fn ecrire(&mut self, donnees: &[u8]) -> Result<(), Self::Erreur> {
+        self.etat.lock().unwrap().recevoir(donnees);
+        Ok(())
+    }
+}
+
+impl LectureSpi for LiaisonSpiSimulee {
+    fn lire(&mut self, tampon: &mut [u8]) -> Result<(), Self::Erreur> {
+        self.etat.lock().unwrap().repondre(tampon);
+        Ok(())
+    }
+}
+
+/// Broche de sortie reliée au contrôleur simulé
+pub struct BrocheSortieSimulee {
+    etat: Arc<Mutex<EtatControleur>>,
+    broche: Broche,
+}
+
+impl BrocheSortieSimulee {
+    fn changer_niveau(&mut self, haut: bool) {
+        let mut etat = self.etat.lock().unwrap();
+        match self.broche {
+            Broche::Dc => etat.dc_haut = haut,
+            Broche::Cs => etat.cs_haut = haut,
+            Broche::Rst => etat.changer_rst(haut),
+        }
+    }
+}
+
+impl BrocheSortie for BrocheSortieSimulee {
+    fn mettre_niveau_haut(&mut self) {
+        self.changer_niveau(true);
+    }
+
+    fn mettre_niveau_bas(&mut self) {
+        self.changer_niveau(false);
+    }
+}
+
+/// Broche BUSY reliée au contrôleur simulé
+pub struct BrocheEntreeSimulee {
+    etat: Arc<Mutex<EtatControleur>>,
+}
+
+impl BrocheEntree for BrocheEntreeSimulee {
+    fn est_niveau_bas(&self) -> bool {
+        self.etat.lock().unwrap().lire_busy()
+    }
+
+    fn surveiller_fronts(&mut self, rappel: Box<dyn FnMut() + Send>) -> bool {
+        let mut etat = self.etat.lock().unwrap();
+        if etat.interruptions {
+            etat.rappel_busy = Some(rappel);
+        }
+        etat.interruptions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecran::memoire_tampon::TriCouleur;
+
+    fn commande(code: u8, donnees: &[u8]) -> Commande {
+        Commande {
+            code,
+            donnees: donnees.to_vec(),
+        }
+    }
+
+    /// Commandes reçues, les lectures successives de la broche BUSY étant regroupées
+    fn commandes_regroupees(controleur: &ControleurSimule) -> Vec<Commande> {
+        let mut commandes = controleur.commandes();
+        commandes.dedup_by(|commande, precedente| {
+            commande.code == Instruction::BusyStatus as u8 && commande == precedente
+        });
+        commandes
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn sequence_initialisation_mise_a_jour_extinction() {
+        let controleur = ControleurSimule::new();
+        let mut ecran = controleur.ecran();
+        ecran.definir_maintien_actif(true);
+        ecran
+            .memoire_tampon_mut()
+            .definir_pixel(0, 0, TriCouleur::Noir);
+        ecran
+            .memoire_tampon_mut()
+            .definir_pixel(8, 0, TriCouleur::Rouge);
+
+        ecran.initialiser().await.unwrap();
+        ecran.mettre_a_jour().await.unwrap();
+        ecran.eteindre().await.unwrap();
+
+        let taille_plan = Modele7In5BV2::LARGEUR * Modele7In5BV2::HAUTEUR / 8;
+        let mut plan_noir = vec![0xFF; taille_plan];
+        plan_noir[0] = 0x7F;
+        let mut plan_rouge = vec![0x00; taille_plan];
+        plan_rouge[1] = 0x80;
+        let attente = commande(0x71, &[]);
+        assert_eq!(
+            commandes_regroupees(&controleur),
+            vec![
+                commande(0x01, &[0x07, 0x07, 0x3F, 0x3F]),
+                commande(0x06, &[0x17, 0x17, 0x28, 0x17]),
+                commande(0x04, &[0xF7]),
+                attente.clone(),
+                commande(0x00, &[0x0F]),
+                commande(0x61, &[0x03, 0x20, 0x01, 0xE0]),
+                commande(0x15, &[0x00]),
+                commande(0x50, &[0x11, 0x07]),
+                commande(0x60, &[0x22]),
+                commande(0x65, &[0x00, 0x00, 0x00, 0x00]),
+                commande(0x10, &plan_noir),
+                commande(0x13, &plan_rouge),
+                commande(0x12, &[]),
+                attente.clone(),
+                commande(0x02, &[]),
+                attente,
+                commande(0x07, &[0xA5]),
+            ]
+        );
+        assert!(controleur.est_en_veille_profonde());
+        assert_eq!(controleur.nombre_reinitialisations(), 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn attente_occupation() {
+        let controleur = ControleurSimule::new();
+        controleur.definir_duree_occupation(3);
+        let mut ecran = controleur.ecran();
+
+        ecran.initialiser().await.unwrap();
+
+        // Trois lectures occupées puis une lecture disponible après la mise sous tension
+        let lectures = controleur
+            .instructions()
+            .into_iter()
+            .skip_while(|instruction| *instruction != Instruction::PowerOn)
+            .take_while(|instruction| *instruction != Instruction::PanelSetting)
+            .filter(|instruction| *instruction == Instruction::BusyStatus)
+            .count();
+        assert_eq!(lectures, 4);
+        assert!(!controleur.est_occupe());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn instructions_refusees_en_veille_profonde() {
+        let controleur = ControleurSimule::new();
+        let mut ecran = controleur.ecran();
+
+        ecran.initialiser().await.unwrap();
+        ecran.eteindre().await.unwrap();
+        controleur.effacer_journal();
+
+        assert!(ecran.envoyer_donnees(&[0x00]).is_err());
+        assert!(controleur.commandes().is_empty());
+
+        // La mise à jour réinitialise le contrôleur
+        ecran.mettre_a_jour().await.unwrap();
+        assert_eq!(controleur.nombre_reinitialisations(), 2);
+        assert!(controleur.est_en_veille_profonde());
+    }
+}
@@ -0,0 +1,301 @@
+use std::{fs, path::Path};
+
+use resvg::{
+    tiny_skia::{Pixmap, PremultipliedColorU8, Transform},
+    usvg::{Options, Tree},
+};
+
+#[cfg(feature = "rendu")]
+use crate::ecran::widget::{Contexte, Widget};
+use crate::ecran::{
+    erreur::ErreurEcran,
+    memoire_tampon::{MemoireTampon, TriCouleur},
+    zone::Zone,
+};
+
+/// Seuils utilisés pour ramener les couleurs d'un document SVG à celles de l'écran
+///
+/// Les pixels semi-transparents sont d'abord mélangés avec du blanc.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct SeuilsCouleur {
+    /// Opacité minimale (0 à 255) d'un pixel dessiné. Les pixels plus transparents laissent la
+    /// mémoire tampon inchangée.
+    pub opacite: u8,
+    /// Luminance (0 à 255) en dessous de laquelle un pixel est noir
+    pub noir: u8,
+    /// Ecart minimal entre la composante rouge et les composantes verte et bleue d'un pixel
+    /// rouge, ou aucun pour un écran noir et blanc
+    pub rouge: Option<u8>,
+}
+
+impl Default for SeuilsCouleur {
+    fn default() -> Self {
+        Self {
+            opacite: 128,
+            noir: 128,
+            rouge: Some(96),
+        }
+    }
+}
+
+impl SeuilsCouleur {
+    /// Couleur de l'écran d'un pixel RGBA prémultiplié, ou aucune s'il est trop transparent
+    fn couleur(&self, pixel: PremultipliedColorU8) -> Option<TriCouleur> {
+        if pixel.alpha() < self.opacite {
+            return None;
+        }
+
+        // Mélange avec du blanc des composantes prémultipliées
+        let blanc = 255 - pixel.alpha() as i32;
+        let [r, g, b] = [pixel.red(), pixel.green(), pixel.blue()].map(|c| c as i32 + blanc);
+        if let Some(rouge) = self.rouge {
+            if r - g.max(b) >= rouge as i32 {
+                return Some(TriCouleur::Rouge);
+            }
+        }
+        let luminance = (299 * r + 587 * g + 114 * b) / 1000;
+        match luminance < self.noir as i32 {
+            true => Some(TriCouleur::Noir),
+            false => Some(TriCouleur::Blanc),
+        }
+    }
+}
+
+/// Document SVG dessiné dans la mémoire tampon à la résolution de l'écran
+///
+/// Le document est agrandi ou réduit en conservant ses proportions pour occuper la zone de
+/// dessin, puis centré. Les textes utilisent les polices installées sur le système.
+pub struct ImageSvg {
+    arbre: Tree,
+    seuils: SeuilsCouleur,
+}
+
+impl ImageSvg {
+    /// Lire un document SVG
+    pub fn new(donnees: &[u8]) -> Result<Self, ErreurEcran> {
+        Self::analyser(donnees, Options::default())
+    }
+
+    /// Lire un fichier SVG. Les images liées sont recherchées à partir de son répertoire.
+    pub fn lire(chemin: impl AsRef<Path>) -> Result<Self, ErreurEcran> {
+        let chemin = chemin.as_ref();
+        let options = Options {
+            resources_dir: chemin.parent().map(Path::to_path_buf),
+            ..Options::default()
+        };
+        Self::analyser(&fs::read(chemin)?, options)
+    }
+
+    fn analyser(donnees: &[u8], mut options: Options) -> Result<Self, ErreurEcran> {
+        options.fontdb_mut().load_system_fonts();
+        let arbre = Tree::from_data(donnees, &options)
+            .map_err(|erreur| ErreurEcran::SvgInvalide(erreur.to_string()))?;
+        Ok(Self {
+            arbre,
+            seuils: SeuilsCouleur::default(),
+        })
+    }
+
+    /// Choisir les seuils de conversion des couleurs
+    pub fn avec_seuils(mut self, seuils: SeuilsCouleur) -> Self {
+        self.seuils = seuils;
+        self
+    }
+
+    /// Seuils de conversion des couleurs
+    pub fn seuils(&self) -> SeuilsCouleur {
+        self.seuils
+    }
+
+    /// Largeur du document en pixels
+    pub fn largeur(&self) -> f32 {
+        self.arbre.size().width()
+    }
+
+    /// Hauteur du document en pixels
+    pub fn hauteur(&self) -> f32 {
+        self.arbre.size().height()
+    }
+
+    /// Dimensions du document agrandi ou réduit pour occuper l'espace disponible
+    fn dimensions(&self, largeur: usize, hauteur: usize) -> (usize, usize, f32) {
+        let echelle = (largeur as f32 / self.largeur()).min(hauteur as f32 / self.hauteur());
+        (
+            ((self.largeur() * echelle).round() as usize).min(largeur),
+            ((self.hauteur() * echelle).round() as usize).min(hauteur),
+            echelle,
+        )
+    }
+
+    /// Mémoire tampon blanche contenant le document
+    pub fn en_memoire_tampon(&self, largeur: usize, hauteur: usize) -> MemoireTampon {
+        let mut memoire_tampon = MemoireTampon::new(largeur, hauteur);
+        self.dessiner_zone(
+            &mut memoire_tampon,
+            Zone::new(0, 0, largeur, hauteur),
+            &self.seuils,
+        );
+        memoire_tampon
+    }
+
+    /// Dessiner le document dans une zone de la mémoire tampon. Les parties transparentes
+    /// laissent la mémoire tampon inchangée.
+    pub fn dessiner_zone(
+        &self,
+        memoire_tampon: &mut MemoireTampon,
+        zone: Zone,
+        seuils: &SeuilsCouleur,
+    ) {
+        let (largeur, hauteur, echelle) = self.dimensions(zone.largeur, zone.hauteur);
+        let Some(mut pixmap) = Pixmap::new(largeur as u32, hauteur as u32) else {
+            log::warn!(
+                "Zone trop petite pour le document SVG : {}×{} pixels",
+                zone.largeur,
+                zone.hauteur
+            );
+            return;
+        };
+        resvg::render(
+            &self.arbre,
+            Transform::from_scale(echelle, echelle),
+            &mut pixmap.as_mut(),
+        );
+
+        let x0 = zone.x + (zone.largeur - largeur) / 2;
+        let y0 = zone.y + (zone.hauteur - hauteur) / 2;
+        for (i, pixel) in pixmap.pixels().iter().enumerate() {
+            if let Some(couleur) = seuils.couleur(*pixel) {
+                memoire_tampon.definir_pixel(x0 + i % largeur, y0 + i / largeur, couleur);
+            }
+        }
+    }
+}
+
+#[cfg(feature = "rendu")]
+impl Widget for ImageSvg {
+    fn mesurer(&self, _contexte: &Contexte, largeur: usize, hauteur: usize) -> (usize, usize) {
+        let (largeur, hauteur, _) = self.dimensions(largeur, hauteur);
+        (largeur, hauteur)
+    }
+
+    fn dessiner(&self, _contexte: &Contexte, memoire_tampon: &mut MemoireTampon, zone: Zone) {
+        self.dessiner_zone(memoire_tampon, zone, &self.seuils);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Document de 40×20 : carré rouge à gauche, carré noir semi-transparent à droite
+    const DOCUMENT: &str = r##"<svg xmlns="http://www.w3.org/2000/svg" width="40" height="20">
+        <rect x="0" y="0" width="20" height="20" fill="#ff0000"/>
+        <rect x="20" y="0" width="20" height="10" fill="#000000" fill-opacity="0.8"/>
+    </svg>"##;
+
+    fn pixel(r: u8, g: u8, b: u8, a: u8) -> PremultipliedColorU8 {
+        PremultipliedColorU8::from_rgba(r, g, b, a).unwrap()
+    }
+
+    #[test]
+    fn seuils_de_couleur() {
+        let seuils = SeuilsCouleur::default();
+        assert_eq!(
+            seuils.couleur(pixel(255, 0, 0, 255)),
+            Some(TriCouleur::Rouge)
+        );
+        assert_eq!(seuils.couleur(pixel(0, 0, 0, 255)), Some(TriCouleur::Noir));
+        assert_eq!(
+            seuils.couleur(pixel(255, 255, 255, 255)),
+            Some(TriCouleur::Blanc)
+        );
+        assert_eq!(
+            seuils.couleur(pixel(127, 127, 127, 255)),
+            Some(TriCouleur::Noir)
+        );
+        assert_eq!(
+            seuils.couleur(pixel(128, 128, 128, 255)),
+            Some(TriCouleur::Blanc)
+        );
+        // Rouge pas assez saturé : ramené au noir ou au blanc selon sa luminance
+        assert_eq!(
+            seuils.couleur(pixel(200, 120, 120, 255)),
+            Some(TriCouleur::Blanc)
+        );
+
+        // Sans rouge, un pixel rouge est ramené au noir selon sa luminance
+        let noir_blanc = SeuilsCouleur {
+            rouge: None,
+            ..seuils
+        };
+        assert_eq!(
+            noir_blanc.couleur(pixel(255, 0, 0, 255)),
+            Some(TriCouleur::Noir)
+        );
+    }
+
+    #[test]
+    fn melange_avec_le_blanc() {
+        let seuils = SeuilsCouleur::default();
+        // Trop transparent : la mémoire tampon reste inchangée
+        assert_eq!(seuils.couleur(pixel(0, 0, 0, 127)), None);
+        // Noir opaque à 50 % : luminance 127 après le mélange
+        assert_eq!(seuils.couleur(pixel(0, 0, 0, 128)), Some(TriCouleur::Noir));
+        let clair = SeuilsCouleur {
+            noir: 100,
+            ..seuils
+        };
+        assert_eq!(clair.couleur(pixel(0, 0, 0, 128)), Some(TriCouleur::Blanc));
+        // Rouge opaque à 50 % : (255, 127, 127) après le mélange
+        assert_eq!(
+            seuils.couleur(pixel(128, 0, 0, 128)),
+            Some(TriCouleur::Rouge)
+        );
+        let sature = SeuilsCouleur {
+            rouge: Some(129),
+            ..seuils
+        };
+        assert_eq!(
+            sature.couleur(pixel(128, 0, 0, 128)),
+            Some(TriCouleur::Blanc)
+        );
+    }
+
+    #[test]
+    fn rendu_dans_la_memoire_tampon() {
+        let image = ImageSvg::new(DOCUMENT.as_bytes()).unwrap();
+        assert_eq!((image.largeur(), image.hauteur()), (40., 20.));
+
+        // Agrandi deux fois (80×40) et centré verticalement dans 80×60
+        let memoire_tampon = image.en_memoire_tampon(80, 60);
+        assert_eq!(memoire_tampon.pixel(0, 10), Some(TriCouleur::Rouge));
+        assert_eq!(memoire_tampon.pixel(39, 49), Some(TriCouleur::Rouge));
+        assert_eq!(memoire_tampon.pixel(0, 9), Some(TriCouleur::Blanc));
+        assert_eq!(memoire_tampon.pixel(0, 50), Some(TriCouleur::Blanc));
+        assert_eq!(memoire_tampon.pixel(40, 10), Some(TriCouleur::Noir));
+        assert_eq!(memoire_tampon.pixel(79, 29), Some(TriCouleur::Noir));
+        assert_eq!(memoire_tampon.pixel(79, 30), Some(TriCouleur::Blanc));
+    }
+
+    #[test]
+    fn parties_transparentes_inchangees() {
+        let image = ImageSvg::new(DOCUMENT.as_bytes()).unwrap();
+        let mut memoire_tampon = MemoireTampon::new(48, 24);
+        memoire_tampon.effacer(TriCouleur::Rouge);
+
+        // Document à sa taille dans une zone de 40×20 : le bas du carré noir reste transparent
+        image.dessiner_zone(
+            &mut memoire_tampon,
+            Zone::new(8, 4, 40, 20),
+            &SeuilsCouleur::default(),
+        );
+        assert_eq!(memoire_tampon.pixel(30, 8), Some(TriCouleur::Noir));
+        assert_eq!(memoire_tampon.pixel(30, 18), Some(TriCouleur::Rouge));
+        assert_eq!(memoire_tampon.pixel(7, 8), Some(TriCouleur::Rouge));
+
+        assert!(matches!(
+            ImageSvg::new(b"<svg"),
+            Err(ErreurEcran::SvgInvalide(_))
+        ));
+    }
+}
@@ -0,0 +1,155 @@
+use crate::ecran::erreur::ErreurEcran;
+
+/// Taille maximale d'une table en octets (10 groupes de 6 octets)
+const TAILLE_MAXIMALE: usize = 60;
+
+/// Nombre d'octets d'un groupe de la table (niveaux puis durées des 4 phases et répétitions)
+const TAILLE_GROUPE: usize = 6;
+
+// Tables de la bibliothèque C de Waveshare pour le 7.5" V2 (dépôt waveshare/e-Paper, fichier
+// RaspberryPi_JetsonNano/c/lib/e-Paper/EPD_7in5_V2.c) : tableaux LUT_VCOM_7IN5_V2,
+// LUT_WW_7IN5_V2 et LUT_BW_7IN5_V2 (vers le blanc), LUT_WB_7IN5_V2 et LUT_BB_7IN5_V2 (vers le
+// noir), chargés par EPD_7IN5_V2_LUT dans les registres 0x20 à 0x24 avec le réglage du panneau
+// 0x3F. Seuls les 3 premiers groupes sont non nuls, soit environ 2,5 s.
+const RAPIDE_VCOM: [u8; 18] = [
+    0x00, 0x0F, 0x0F, 0x00, 0x00, 0x01, //
+    0x00, 0x0F, 0x01, 0x0F, 0x01, 0x02, //
+    0x00, 0x0F, 0x0F, 0x00, 0x00, 0x01, //
+];
+const RAPIDE_VERS_BLANC: [u8; 18] = [
+    0x10, 0x0F, 0x0F, 0x00, 0x00, 0x01, //
+    0x84, 0x0F, 0x01, 0x0F, 0x01, 0x02, //
+    0x20, 0x0F, 0x0F, 0x00, 0x00, 0x01, //
+];
+const RAPIDE_VERS_NOIR: [u8; 18] = [
+    0x80, 0x0F, 0x0F, 0x00, 0x00, 0x01, //
+    0x84, 0x0F, 0x01, 0x0F, 0x01, 0x02, //
+    0x40, 0x0F, 0x0F, 0x00, 0x00, 0x01, //
+];
+
+// Réglage 4 niveaux de gris de l'implémentation de Waveshare pour le 7.5" V2 (epd7in5_V2.py,
+// init_4Gray) : démarrage renforcé du convertisseur, puis température imposée (cascade 0x02,
+// température 0x5F) pour choisir les formes d'onde en niveaux de gris de la mémoire OTP
+pub(crate) const GRIS_DEMARRAGE_CONVERTISSEUR: [u8; 4] = [0x27, 0x27, 0x18, 0x17];
+pub(crate) const GRIS_CASCADE: u8 = 0x02;
+pub(crate) const GRIS_TEMPERATURE: u8 = 0x5F;
+
+/// Tables de formes d'onde chargées dans les registres du contrôleur (0x20 à 0x24)
+///
+/// Par défaut, le contrôleur utilise les formes d'onde enregistrées dans sa mémoire OTP, qui
+/// provoquent le long clignotement du rafraîchissement en trois couleurs. Une table permet de
+/// choisir des formes d'onde plus rapides.
+///
+/// Chaque table est composée de groupes de 6 octets : les niveaux de tension des 4 phases, la
+/// durée en trames de chaque phase et le nombre de répétitions du groupe. Les tables sont vides
+/// pour [`TableForme::gris_4_niveaux`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TableForme {
+    vcom: Vec<u8>,
+    ww: Vec<u8>,
+    bw: Vec<u8>,
+    wb: Vec<u8>,
+    bb: Vec<u8>,
+    noir_blanc: bool,
+    gris: bool,
+}
+
+impl TableForme {
+    /// Table de formes d'onde : VCOM puis transitions blanc→blanc, noir→blanc, blanc→noir et
+    /// noir→noir. Chaque table contient au plus 10 groupes de 6 octets.
+    pub fn new(
+        vcom: Vec<u8>,
+        ww: Vec<u8>,
+        bw: Vec<u8>,
+        wb: Vec<u8>,
+        bb: Vec<u8>,
+    ) -> Result<Self, ErreurEcran> {
+        for table in [&vcom, &ww, &bw, &wb, &bb] {
+            if table.is_empty() || table.len() > TAILLE_MAXIMALE || table.len() % TAILLE_GROUPE != 0
+            {
+                return Err(ErreurEcran::TableFormeInvalide { taille: table.len() });
+            }
+        }
+
+        Ok(Self {
+            vcom,
+            ww,
+            bw,
+            wb,
+            bb,
+            noir_blanc: false,
+            gris: false,
+        })
+    }
+
+    /// Table rapide noir et blanc (environ 2,5 s). Les pixels rouges sont affichés en blanc.
+    pub fn rapide_noir_blanc() -> Self {
+        Self {
+            vcom: RAPIDE_VCOM.to_vec(),
+            ww: RAPIDE_VERS_BLANC.to_vec(),
+            bw: RAPIDE_VERS_BLANC.to_vec(),
+            wb: RAPIDE_VERS_NOIR.to_vec(),
+            bb: RAPIDE_VERS_NOIR.to_vec(),
+            noir_blanc: true,
+            gris: false,
+        }
+    }
+
+    /// Réglage 4 niveaux de gris ([`crate::ecran::memoire_tampon::Gris4`]) pour le 7.5" V2.
+    /// Aucune table n'est chargée : le contrôleur utilise les formes d'onde en niveaux de gris
+    /// de sa mémoire OTP, choisies par une température imposée. Le plan noir et le plan rouge
+    /// sont transmis tous les deux et choisissent ensemble le niveau de chaque pixel.
+    pub fn gris_4_niveaux() -> Self {
+        Self {
+            vcom: Vec::new(),
+            ww: Vec::new(),
+            bw: Vec::new(),
+            wb: Vec::new(),
+            bb: Vec::new(),
+            noir_blanc: false,
+            gris: true,
+        }
+    }
+
+    /// Utiliser la table en mode noir et blanc : le plan rouge n'est pas transmis et les pixels
+    /// rouges sont affichés en blanc
+    pub fn en_noir_blanc(mut self) -> Self {
+        self.noir_blanc = true;
+        self
+    }
+
+    /// Table VCOM (registre 0x20)
+    pub fn vcom(&self) -> &[u8] {
+        &self.vcom
+    }
+
+    /// Table de la transition blanc→blanc (registre 0x21)
+    pub fn ww(&self) -> &[u8] {
+        &self.ww
+    }
+
+    /// Table de la transition noir→blanc (registre 0x22)
+    pub fn bw(&self) -> &[u8] {
+        &self.bw
+    }
+
+    /// Table de la transition blanc→noir (registre 0x23)
+    pub fn wb(&self) -> &[u8] {
+        &self.wb
+    }
+
+    /// Table de la transition noir→noir (registre 0x24)
+    pub fn bb(&self) -> &[u8] {
+        &self.bb
+    }
+
+    /// Indiquer si la table est utilisée en mode noir et blanc
+    pub fn est_noir_blanc(&self) -> bool {
+        self.noir_blanc
+    }
+
+    /// Indiquer si la table affiche 4 niveaux de gris
+    pub fn est_gris(&self) -> bool {
+        self.gris
+    }
+}
@@ -0,0 +1,51 @@
+/// Contrôle de la température avant un rafraîchissement de l'écran
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum ControleTemperature {
+    /// Ne pas contrôler la température
+    Ignorer,
+    /// Journaliser un avertissement si la dernière température mesurée est hors de la plage
+    /// de fonctionnement du modèle
+    #[default]
+    Avertir,
+    /// Refuser le rafraîchissement si la dernière température mesurée est hors de la plage de
+    /// fonctionnement du modèle
+    Refuser,
+}
+
+/// Convertir les deux octets renvoyés par le capteur de température du contrôleur en °C.
+/// La température est codée sur 11 bits en complément à deux, par pas de 0,125 °C.
+pub(crate) fn decoder(octets: [u8; 2]) -> f32 {
+    (i16::from_be_bytes(octets) >> 5) as f32 * 0.125
+}
+
+/// Convertir une température en °C vers les deux octets renvoyés par le capteur
+pub(crate) fn encoder(temperature: f32) -> [u8; 2] {
+    let valeur = ((temperature / 0.125).round() as i16) << 5;
+    valeur.to_be_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodage() {
+        assert_eq!(decoder([0x00, 0x00]), 0.0);
+        assert_eq!(decoder([0x19, 0x00]), 25.0);
+        assert_eq!(decoder([0x00, 0x20]), 0.125);
+        // Les 5 bits de poids faible sont ignorés
+        assert_eq!(decoder([0x19, 0x1F]), 25.0);
+        assert_eq!(decoder([0xFF, 0xE0]), -0.125);
+        assert_eq!(decoder([0xF6, 0x00]), -10.0);
+        assert_eq!(decoder([0xEC, 0x00]), -20.0);
+        assert_eq!(decoder([0x80, 0x00]), -128.0);
+    }
+
+    #[test]
+    fn encodage() {
+        for temperature in [-40.0, -20.0, -0.125, 0.0, 0.125, 25.0, 60.5] {
+            assert_eq!(decoder(encoder(temperature)), temperature);
+        }
+        assert_eq!(encoder(-20.0), [0xEC, 0x00]);
+    }
+}
@@ -0,0 +1,602 @@
+use std::{fs, path::Path};
+
+use rusttype::{point, Font, GlyphId, Scale};
+
+use crate::ecran::{
+    erreur::ErreurEcran,
+    memoire_tampon::{MemoireTampon, TriCouleur},
+    zone::Zone,
+};
+
+/// Points de suspension ajoutés aux lignes tronquées
+const POINTS_SUSPENSION: &str = "…";
+
+/// Points de suspension utilisés si aucune police ne contient le caractère « … »
+const POINTS_SUSPENSION_REPLI: &str = "...";
+
+/// Alignement horizontal des lignes
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "mise_en_page",
+    derive(serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum Alignement {
+    /// Aligné à gauche
+    #[default]
+    Gauche,
+    /// Centré
+    Centre,
+    /// Aligné à droite
+    Droite,
+    /// Justifié : les espaces sont élargis, sauf sur la dernière ligne des paragraphes
+    Justifie,
+}
+
+/// Alignement vertical du texte dans la zone
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "mise_en_page",
+    derive(serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum AlignementVertical {
+    /// En haut de la zone
+    #[default]
+    Haut,
+    /// Au milieu de la zone
+    Milieu,
+    /// En bas de la zone
+    Bas,
+}
+
+/// Style du texte
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "mise_en_page",
+    derive(serde::Deserialize),
+    serde(default, deny_unknown_fields)
+)]
+pub struct StyleTexte {
+    /// Taille de la police en pixels
+    pub taille: f32,
+    /// Couleur du texte
+    pub couleur: TriCouleur,
+    /// Alignement horizontal
+    pub alignement: Alignement,
+    /// Alignement vertical
+    pub alignement_vertical: AlignementVertical,
+    /// Facteur appliqué à la hauteur des lignes
+    pub interligne: f32,
+    /// Couper les lignes trop longues entre les mots
+    pub retour_a_la_ligne: bool,
+    /// Terminer par « … » les lignes tronquées
+    pub points_suspension: bool,
+    /// Couverture minimale d'un pixel pour qu'il soit dessiné, entre 0 et 1
+    pub seuil: f32,
+}
+
+impl Default for StyleTexte {
+    fn default() -> Self {
+        Self::new(24.)
+    }
+}
+
+impl StyleTexte {
+    /// Texte noir aligné en haut à gauche, avec retour à la ligne et points de suspension
+    pub fn new(taille: f32) -> Self {
+        Self {
+            taille,
+            couleur: TriCouleur::Noir,
+            alignement: Alignement::default(),
+            alignement_vertical: AlignementVertical::default(),
+            interligne: 1.,
+            retour_a_la_ligne: true,
+            points_suspension: true,
+            seuil: 0.5,
+        }
+    }
+
+    /// Choisir la couleur du texte
+    pub fn avec_couleur(mut self, couleur: TriCouleur) -> Self {
+        self.couleur = couleur;
+        self
+    }
+
+    /// Choisir l'alignement horizontal
+    pub fn avec_alignement(mut self, alignement: Alignement) -> Self {
+        self.alignement = alignement;
+        self
+    }
+
+    /// Choisir l'alignement vertical
+    pub fn avec_alignement_vertical(mut self, alignement_vertical: AlignementVertical) -> Self {
+        self.alignement_vertical = alignement_vertical;
+        self
+    }
+
+    /// Vérifier que la taille et l'interligne sont des nombres finis et positifs
+    pub fn verifier(&self) -> Result<(), ErreurEcran> {
+        if !(self.taille.is_finite() && self.taille > 0.) {
+            return Err(ErreurEcran::StyleTexteInvalide(format!("taille {}", self.taille)));
+        }
+        if !(self.interligne.is_finite() && self.interligne > 0.) {
+            return Err(ErreurEcran::StyleTexteInvalide(format!(
+                "interligne {}",
+                self.interligne
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Métriques verticales de la police principale, en pixels
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Metriques {
+    /// Hauteur au-dessus de la ligne de base
+    pub ascendante: f32,
+    /// Hauteur sous la ligne de base (négative)
+    pub descendante: f32,
+    /// Espace recommandé entre deux lignes
+    pub espace_lignes: f32,
+}
+
+impl Metriques {
+    /// Hauteur d'une ligne, de la ligne de base à la suivante
+    pub fn hauteur_ligne(&self) -> f32 {
+        self.ascendante - self.descendante + self.espace_lignes
+    }
+}
+
+/// Ligne de texte positionnée dans la zone
+#[derive(Clone, Debug, PartialEq)]
+pub struct LigneTexte {
+    /// Texte de la ligne
+    pub texte: String,
+    /// Abscisse du début de la ligne par rapport à la zone
+    pub x: f32,
+    /// Ordonnée de la ligne de base par rapport à la zone
+    pub ligne_de_base: f32,
+    /// Largeur de la ligne sans justification
+    pub largeur: f32,
+    /// Espace ajouté à chaque espace pour justifier la ligne
+    pub espacement: f32,
+}
+
+/// Texte disposé dans une zone
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Disposition {
+    /// Lignes affichées
+    pub lignes: Vec<LigneTexte>,
+    /// Indique si le texte ne tient pas entièrement dans la zone
+    pub tronquee: bool,
+}
+
+/// Polices de caractères avec chaîne de repli
+///
+/// Chaque caractère est dessiné avec la première police qui le contient. Les métriques
+/// verticales sont celles de la police principale.
+pub struct Polices {
+    polices: Vec<Font<'static>>,
+}
+
+impl Polices {
+    /// Polices composées d'une police principale
+    pub fn new(police: Font<'static>) -> Self {
+        Self {
+            polices: vec![police],
+        }
+    }
+
+    /// Charger la police principale depuis un fichier TrueType ou OpenType
+    pub fn depuis_fichier(chemin: impl AsRef<Path>) -> Result<Self, ErreurEcran> {
+        Ok(Self::new(Self::charger(chemin.as_ref())?))
+    }
+
+    /// Ajouter une police de repli
+    pub fn avec_repli(mut self, police: Font<'static>) -> Self {
+        self.polices.push(police);
+        self
+    }
+
+    /// Ajouter une police de repli chargée depuis un fichier
+    pub fn avec_fichier_repli(self, chemin: impl AsRef<Path>) -> Result<Self, ErreurEcran> {
+        Ok(self.avec_repli(Self::charger(chemin.as_ref())?))
+    }
+
+    fn charger(chemin: &Path) -> Result<Font<'static>, ErreurEcran> {
+        Font::try_from_vec(fs::read(chemin)?)
+            .ok_or_else(|| ErreurEcran::PoliceInvalide(chemin.to_path_buf()))
+    }
+
+    /// Indice de la police contenant le caractère, ou de la police principale si aucune ne le
+    /// contient
+    fn police(&self, caractere: char) -> usize {
+        self.polices
+            .iter()
+            .position(|police| police.glyph(caractere).id() != GlyphId(0))
+            .unwrap_or(0)
+    }
+
+    /// Métriques verticales de la police principale
+    pub fn metriques(&self, taille: f32) -> Metriques {
+        let metriques = self.polices[0].v_metrics(Scale::uniform(taille));
+        Metriques {
+            ascendante: metriques.ascent,
+            descendante: metriques.descent,
+            espace_lignes: metriques.line_gap,
+        }
+    }
+
+    /// Largeur d'un texte sur une ligne, en pixels
+    pub fn mesurer(&self, texte: &str, taille: f32) -> f32 {
+        self.placer(texte, taille, 0.).1
+    }
+
+    /// Indice de la police et abscisse de chaque caractère d'une ligne, et largeur de la ligne.
+    /// L'espacement est ajouté après chaque espace.
+    fn placer(&self, texte: &str, taille: f32, espacement: f32) -> (Vec<(usize, char, f32)>, f32) {
+        let echelle = Scale::uniform(taille);
+        let mut caracteres = Vec::with_capacity(texte.len());
+        let mut x = 0.;
+        let mut precedent: Option<(usize, GlyphId)> = None;
+        for caractere in texte.chars() {
+            let indice = self.police(caractere);
+            let police = &self.polices[indice];
+            let glyphe = police.glyph(caractere);
+            // Le crénage n'est défini qu'entre deux glyphes de la même police
+            if let Some((indice_precedent, id_precedent)) = precedent {
+                if indice_precedent == indice {
+                    x += police.pair_kerning(echelle, id_precedent, glyphe.id());
+                }
+            }
+            precedent = Some((indice, glyphe.id()));
+            caracteres.push((indice, caractere, x));
+            x += glyphe.scaled(echelle).h_metrics().advance_width;
+            if caractere == ' ' {
+                x += espacement;
+            }
+        }
+        (caracteres, x)
+    }
+
+    /// Disposer un texte dans une zone de dimensions données.
+    /// Les retours à la ligne du texte séparent les paragraphes.
+    pub fn disposer(
+        &self,
+        texte: &str,
+        style: &StyleTexte,
+        largeur: f32,
+        hauteur: f32,
+    ) -> Disposition {
+        let mut lignes: Vec<(String, bool)> = Vec::new();
+        for paragraphe in texte.lines() {
+            match style.retour_a_la_ligne {
+                true => self.couper(paragraphe, style.taille, largeur, &mut lignes),
+                false => lignes.push((paragraphe.trim_end().to_string(), true)),
+            }
+        }
+
+        let metriques = self.metriques(style.taille);
+        let hauteur_texte = metriques.ascendante - metriques.descendante;
+        let hauteur_ligne = metriques.hauteur_ligne() * style.interligne;
+        // Une seule ligne si l'interligne ne sépare pas les lignes (style non vérifié)
+        let nombre_maximal = match hauteur >= hauteur_texte {
+            true if hauteur_ligne > 0. => {
+                1usize.saturating_add(((hauteur - hauteur_texte) / hauteur_ligne).floor() as usize)
+            }
+            true => 1,
+            false => 0,
+        };
+        let tronquee = lignes.len() > nombre_maximal;
+        lignes.truncate(nombre_maximal);
+
+        let nombre_lignes = lignes.len();
+        let mut disposition = Disposition {
+            lignes: Vec::with_capacity(nombre_lignes),
+            tronquee,
+        };
+        if nombre_lignes == 0 {
+            return disposition;
+        }
+        let hauteur_totale = (nombre_lignes - 1) as f32 * hauteur_ligne + hauteur_texte;
+        let y = match style.alignement_vertical {
+            AlignementVertical::Haut => 0.,
+            AlignementVertical::Milieu => (hauteur - hauteur_totale) / 2.,
+            AlignementVertical::Bas => hauteur - hauteur_totale,
+        };
+
+        for (i, (mut texte_ligne, fin_paragraphe)) in lignes.into_iter().enumerate() {
+            let derniere = i + 1 == nombre_lignes;
+            disposition.tronquee |= self.mesurer(&texte_ligne, style.taille) > largeur;
+            if style.points_suspension {
+                texte_ligne = self.tronquer(&texte_ligne, style.taille, largeur, derniere && tronquee);
+            }
+            let largeur_ligne = self.mesurer(&texte_ligne, style.taille);
+            let espaces = texte_ligne.chars().filter(|c| *c == ' ').count();
+            let (x, espacement) = match style.alignement {
+                Alignement::Gauche => (0., 0.),
+                Alignement::Centre => ((largeur - largeur_ligne) / 2., 0.),
+                Alignement::Droite => (largeur - largeur_ligne, 0.),
+                Alignement::Justifie if !fin_paragraphe && espaces > 0 => {
+                    (0., ((largeur - largeur_ligne) / espaces as f32).max(0.))
+                }
+                Alignement::Justifie => (0., 0.),
+            };
+            disposition.lignes.push(LigneTexte {
+                texte: texte_ligne,
+                x,
+                ligne_de_base: y + metriques.ascendante + i as f32 * hauteur_ligne,
+                largeur: largeur_ligne,
+                espacement,
+            });
+        }
+        disposition
+    }
+
+    /// Couper un paragraphe en lignes entre les mots, ou entre les caractères des mots plus
+    /// longs que la zone. Chaque ligne indique si elle termine le paragraphe.
+    fn couper(&self, paragraphe: &str, taille: f32, largeur: f32, lignes: &mut Vec<(String, bool)>) {
+        let mut ligne = String::new();
+        for mot in paragraphe.split_whitespace() {
+            let candidate = match ligne.is_empty() {
+                true => mot.to_string(),
+                false => format!("{ligne} {mot}"),
+            };
+            if self.mesurer(&candidate, taille) <= largeur {
+                ligne = candidate;
+                continue;
+            }
+            if !ligne.is_empty() {
+                lignes.push((std::mem::take(&mut ligne), false));
+            }
+            for caractere in mot.chars() {
+                ligne.push(caractere);
+                if ligne.chars().count() > 1 && self.mesurer(&ligne, taille) > largeur {
+                    ligne.pop();
+                    lignes.push((std::mem::replace(&mut ligne, caractere.to_string()), false));
+                }
+            }
+        }
+        lignes.push((ligne, true));
+    }
+
+    /// Terminer une ligne par des points de suspension si elle est trop longue ou si le texte
+    /// suivant est masqué
+    fn tronquer(&self, ligne: &str, taille: f32, largeur: f32, forcer: bool) -> String {
+        if !forcer && self.mesurer(ligne, taille) <= largeur {
+            return ligne.to_string();
+        }
+        let suspension = match self.polices[self.police('…')].glyph('…').id() != GlyphId(0) {
+            true => POINTS_SUSPENSION,
+            false => POINTS_SUSPENSION_REPLI,
+        };
+
+        let mut caracteres: Vec<char> = ligne.chars().collect();
+        loop {
+            let debut: String = caracteres.iter().collect();
+            let candidate = format!("{}{suspension}", debut.trim_end());
+            if caracteres.is_empty() || self.mesurer(&candidate, taille) <= largeur {
+                return candidate;
+            }
+            caracteres.pop();
+        }
+    }
+
+    /// Dessiner un texte dans une zone de la mémoire tampon, en coordonnées logiques.
+    /// Les pixels hors de la zone ne sont pas modifiés.
+    pub fn dessiner(
+        &self,
+        memoire_tampon: &mut MemoireTampon,
+        zone: Zone,
+        texte: &str,
+        style: &StyleTexte,
+    ) -> Disposition {
+        let disposition = self.disposer(texte, style, zone.largeur as f32, zone.hauteur as f32);
+        let echelle = Scale::uniform(style.taille);
+
+        for ligne in &disposition.lignes {
+            let (caracteres, _) = self.placer(&ligne.texte, style.taille, ligne.espacement);
+            for (indice, caractere, x) in caracteres {
+                let glyphe = self.polices[indice]
+                    .glyph(caractere)
+                    .scaled(echelle)
+                    .positioned(point(
+                        zone.x as f32 + ligne.x + x,
+                        zone.y as f32 + ligne.ligne_de_base,
+                    ));
+                // Les glyphes sans contour, comme les espaces, n'ont pas de boîte englobante
+                let Some(boite) = glyphe.pixel_bounding_box() else {
+                    continue;
+                };
+                glyphe.draw(|gx, gy, couverture| {
+                    let px = boite.min.x + gx as i32;
+                    let py = boite.min.y + gy as i32;
+                    if couverture < style.seuil
+                        || px < zone.x as i32
+                        || py < zone.y as i32
+                        || px >= (zone.x + zone.largeur) as i32
+                        || py >= (zone.y + zone.hauteur) as i32
+                    {
+                        return;
+                    }
+                    memoire_tampon.definir_pixel(px as usize, py as usize, style.couleur);
+                });
+            }
+        }
+        disposition
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Taille des caractères des tests
+    const TAILLE: f32 = 20.;
+
+    /// Police à chasse fixe : chaque caractère a la même largeur
+    fn polices() -> Polices {
+        Polices::depuis_fichier(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/polices/DejaVuSansMono.ttf"
+        ))
+        .unwrap()
+    }
+
+    /// Disposer un texte dans une zone large de 10,5 caractères et haute de 10 lignes
+    fn disposer(polices: &Polices, texte: &str, style: &StyleTexte) -> Disposition {
+        let caractere = polices.mesurer("a", TAILLE);
+        let hauteur = 10. * polices.metriques(TAILLE).hauteur_ligne();
+        polices.disposer(texte, style, 10.5 * caractere, hauteur)
+    }
+
+    fn textes(disposition: &Disposition) -> Vec<&str> {
+        disposition
+            .lignes
+            .iter()
+            .map(|ligne| ligne.texte.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn coupure_entre_les_mots() {
+        let polices = polices();
+        let disposition = disposer(&polices, "un deux trois quatre", &StyleTexte::new(TAILLE));
+        assert_eq!(textes(&disposition), ["un deux", "trois", "quatre"]);
+        assert!(!disposition.tronquee);
+
+        let hauteur_ligne = polices.metriques(TAILLE).hauteur_ligne();
+        let premiere = disposition.lignes[0].ligne_de_base;
+        assert_eq!(premiere, polices.metriques(TAILLE).ascendante);
+        assert_eq!(
+            disposition.lignes[2].ligne_de_base,
+            premiere + 2. * hauteur_ligne
+        );
+    }
+
+    #[test]
+    fn mot_plus_long_que_la_zone() {
+        let polices = polices();
+        let texte = "a abcdefghijklmnopqrstuvwxyz";
+        let disposition = disposer(&polices, texte, &StyleTexte::new(TAILLE));
+        assert_eq!(
+            textes(&disposition),
+            ["a", "abcdefghij", "klmnopqrst", "uvwxyz"]
+        );
+        assert!(!disposition.tronquee);
+    }
+
+    #[test]
+    fn retours_a_la_ligne() {
+        let polices = polices();
+        let disposition = disposer(&polices, "un\n\ndeux  \ntrois", &StyleTexte::new(TAILLE));
+        assert_eq!(textes(&disposition), ["un", "", "deux", "trois"]);
+
+        let mut style = StyleTexte::new(TAILLE);
+        style.retour_a_la_ligne = false;
+        let disposition = disposer(&polices, "un deux trois quatre\ncinq", &style);
+        assert_eq!(textes(&disposition), ["un deux t…", "cinq"]);
+        assert!(disposition.tronquee);
+    }
+
+    #[test]
+    fn texte_vide() {
+        let polices = polices();
+        let disposition = disposer(&polices, "", &StyleTexte::new(TAILLE));
+        assert_eq!(disposition, Disposition::default());
+
+        // Zone moins haute qu'une ligne
+        let disposition = polices.disposer("un", &StyleTexte::new(TAILLE), 100., 1.);
+        assert!(disposition.lignes.is_empty());
+        assert!(disposition.tronquee);
+    }
+
+    #[test]
+    fn texte_masque() {
+        let polices = polices();
+        let hauteur = polices.metriques(TAILLE).hauteur_ligne();
+        let largeur = 10.5 * polices.mesurer("a", TAILLE);
+        let disposition = polices.disposer("un\ndeux", &StyleTexte::new(TAILLE), largeur, hauteur);
+        assert_eq!(textes(&disposition), ["un…"]);
+        assert!(disposition.tronquee);
+    }
+
+    #[test]
+    fn alignements() {
+        let polices = polices();
+        let caractere = polices.mesurer("a", TAILLE);
+        let largeur = 10.5 * caractere;
+        let decalage = |alignement| {
+            let style = StyleTexte::new(TAILLE).avec_alignement(alignement);
+            let disposition = disposer(&polices, "abcd", &style);
+            assert_eq!(
+                disposition.lignes[0].largeur,
+                polices.mesurer("abcd", TAILLE)
+            );
+            disposition.lignes[0].x
+        };
+        assert_eq!(decalage(Alignement::Gauche), 0.);
+        let droite = decalage(Alignement::Droite);
+        assert!((droite - (largeur - 4. * caractere)).abs() < 0.01);
+        assert!((decalage(Alignement::Centre) - droite / 2.).abs() < 0.01);
+
+        // Seules les lignes coupées à l'intérieur d'un paragraphe sont justifiées
+        let style = StyleTexte::new(TAILLE).avec_alignement(Alignement::Justifie);
+        let disposition = disposer(&polices, "un deux trois quatre", &style);
+        let espacement = disposition.lignes[0].espacement;
+        assert!((espacement - (largeur - 7. * caractere)).abs() < 0.01);
+        assert_eq!(disposition.lignes[2].espacement, 0.);
+    }
+
+    #[test]
+    fn alignement_vertical() {
+        let polices = polices();
+        let metriques = polices.metriques(TAILLE);
+        let hauteur_texte = metriques.ascendante - metriques.descendante;
+        let ligne_de_base = |alignement| {
+            let style = StyleTexte::new(TAILLE).avec_alignement_vertical(alignement);
+            polices.disposer("un", &style, 100., 100.).lignes[0].ligne_de_base
+        };
+        assert_eq!(
+            ligne_de_base(AlignementVertical::Haut),
+            metriques.ascendante
+        );
+        assert_eq!(
+            ligne_de_base(AlignementVertical::Bas),
+            100. - hauteur_texte + metriques.ascendante
+        );
+        assert_eq!(
+            ligne_de_base(AlignementVertical::Milieu),
+            (100. - hauteur_texte) / 2. + metriques.ascendante
+        );
+    }
+
+    #[test]
+    fn interligne_nul() {
+        let polices = polices();
+        let style = StyleTexte {
+            interligne: 0.,
+            ..StyleTexte::new(TAILLE)
+        };
+        assert!(matches!(
+            style.verifier(),
+            Err(ErreurEcran::StyleTexteInvalide(_))
+        ));
+
+        // Les lignes se superposeraient : seule la première est affichée
+        let disposition = disposer(&polices, "un\ndeux", &style);
+        assert_eq!(textes(&disposition), ["un…"]);
+        assert!(disposition.tronquee);
+
+        for interligne in [-1., f32::NAN, f32::INFINITY] {
+            let style = StyleTexte {
+                interligne,
+                ..StyleTexte::new(TAILLE)
+            };
+            assert!(style.verifier().is_err(), "{interligne}");
+            assert!(disposer(&polices, "un\ndeux", &style).lignes.len() <= 1);
+        }
+        assert!(StyleTexte::new(TAILLE).verifier().is_ok());
+    }
+}
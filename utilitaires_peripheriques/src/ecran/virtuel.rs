@@ -0,0 +1,408 @@
+use std::{
+    fs,
+    ops::{Deref, DerefMut},
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use crate::ecran::{
+    ecran::MiseAJour,
+    erreur::ErreurEcran,
+    instruction::Instruction,
+    memoire_tampon::{MemoireTampon, TriCouleur},
+    modele::{Modele7In5BV2, ModeleEcran, Plan},
+    simulation::{ControleurSimule, EcranSimule},
+    table_forme::TableForme,
+    zone::Zone,
+};
+
+/// Couleurs RGB utilisées pour représenter l'écran
+const BLANC: [u8; 3] = [255, 255, 255];
+const NOIR: [u8; 3] = [0, 0, 0];
+const ROUGE: [u8; 3] = [255, 0, 0];
+
+/// Format des images enregistrées par l'écran virtuel
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FormatImage {
+    /// Image noir et blanc PBM (P4). Les pixels rouges sont enregistrés en noir.
+    Pbm,
+    /// Image couleur PPM (P6)
+    Ppm,
+    /// Image couleur PNG
+    #[cfg(feature = "image")]
+    Png,
+}
+
+impl FormatImage {
+    /// Déterminer le format à partir de l'extension du fichier
+    pub fn depuis_chemin(chemin: &Path) -> Option<Self> {
+        let extension = chemin.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "pbm" => Some(FormatImage::Pbm),
+            "ppm" => Some(FormatImage::Ppm),
+            #[cfg(feature = "image")]
+            "png" => Some(FormatImage::Png),
+            _ => None,
+        }
+    }
+}
+
+/// Ecran virtuel enregistrant l'image affichée dans un fichier
+///
+/// L'écran virtuel donne accès aux méthodes de [`crate::ecran::ecran::Ecran`] (`Deref`). Les
+/// commandes sont envoyées à un [`ControleurSimule`] et l'image affichée est reconstruite à
+/// partir des plans noir et rouge reçus par le contrôleur lors de chaque rafraîchissement. Elle
+/// reflète donc ce que l'écran affiche réellement après la conversion en plans, y compris lors
+/// des mises à jour partielles.
+///
+/// L'image est enregistrée par [`Self::mettre_a_jour`], [`Self::forcer_mise_a_jour`] et
+/// [`Self::mettre_a_jour_zone`], qui remplacent les méthodes de l'écran.
+pub struct EcranVirtuel<M = Modele7In5BV2> {
+    ecran: EcranSimule<M>,
+    controleur: ControleurSimule,
+    chemin: PathBuf,
+    format: FormatImage,
+    memoire_controleur: MemoireTampon,
+    affichage: MemoireTampon,
+}
+
+impl<M> Deref for EcranVirtuel<M> {
+    type Target = EcranSimule<M>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.ecran
+    }
+}
+
+impl<M> DerefMut for EcranVirtuel<M> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.ecran
+    }
+}
+
+impl<M: ModeleEcran> EcranVirtuel<M> {
+    /// Ecran virtuel enregistrant l'image affichée dans le fichier indiqué après chaque
+    /// rafraîchissement. Le format est déterminé par l'extension du fichier (`pbm`, `ppm` ou
+    /// `png` avec la fonctionnalité `image`).
+    pub fn new(chemin: impl Into<PathBuf>) -> Result<Self, ErreurEcran> {
+        let chemin = chemin.into();
+        let format = FormatImage::depuis_chemin(&chemin)
+            .ok_or_else(|| ErreurEcran::FormatNonPrisEnCharge(chemin.clone()))?;
+        Ok(Self::avec_format(chemin, format))
+    }
+
+    /// Ecran virtuel enregistrant l'image affichée dans le fichier et le format indiqués
+    pub fn avec_format(chemin: impl Into<PathBuf>, format: FormatImage) -> Self {
+        let controleur = ControleurSimule::new();
+        Self {
+            ecran: controleur.ecran_modele(),
+            controleur,
+            chemin: chemin.into(),
+            format,
+            memoire_controleur: MemoireTampon::new(M::LARGEUR, M::HAUTEUR),
+            affichage: MemoireTampon::new(M::LARGEUR, M::HAUTEUR),
+        }
+    }
+
+    /// Contrôleur simulé recevant les commandes de l'écran virtuel
+    pub fn controleur(&self) -> &ControleurSimule {
+        &self.controleur
+    }
+
+    /// Fichier dans lequel l'image affichée est enregistrée
+    pub fn chemin(&self) -> &Path {
+        &self.chemin
+    }
+
+    /// Image affichée par l'écran lors du dernier rafraîchissement, dans l'orientation logique
+    /// de ce rafraîchissement
+    pub fn affichage(&self) -> &MemoireTampon {
+        &self.affichage
+    }
+
+    /// Mettre à jour l'écran et enregistrer l'image affichée si elle a changé
+    pub async fn mettre_a_jour(&mut self) -> Result<MiseAJour, ErreurEcran> {
+        let mise_a_jour = self.ecran.mettre_a_jour().await?;
+        self.actualiser_affichage()?;
+        Ok(mise_a_jour)
+    }
+
+    /// Mettre à jour l'écran même si l'image n'a pas changé et enregistrer l'image affichée
+    pub async fn forcer_mise_a_jour(&mut self) -> Result<(), ErreurEcran> {
+        self.ecran.forcer_mise_a_jour().await?;
+        self.actualiser_affichage()
+    }
+
+    /// Mettre à jour une zone de l'écran et enregistrer l'image affichée si elle a changé
+    pub async fn mettre_a_jour_zone(&mut self, zone: Zone) -> Result<MiseAJour, ErreurEcran> {
+        let mise_a_jour = self.ecran.mettre_a_jour_zone(zone).await?;
+        self.actualiser_affichage()?;
+        Ok(mise_a_jour)
+    }
+
+    /// Restaurer l'image affichée enregistrée dans un fichier. L'écran virtuel affiche
+    /// également cette image, comme un écran physique la conserve hors tension.
+    pub fn restaurer_image_affichee(
+        &mut self,
+        chemin: impl AsRef<Path>,
+    ) -> Result<SystemTime, ErreurEcran> {
+        let horodatage = self.ecran.restaurer_image_affichee(chemin)?;
+        if let Some(image_affichee) = self.ecran.image_affichee() {
+            self.affichage = image_affichee.clone();
+        }
+        self.enregistrer(&self.chemin, self.format)?;
+        Ok(horodatage)
+    }
+
+    /// Enregistrer l'image affichée dans un fichier
+    /// Les niveaux de gris sont enregistrés en gris si la table de formes d'onde est
+    /// [`TableForme::gris_4_niveaux`].
+    pub fn enregistrer(&self, chemin: &Path, format: FormatImage) -> Result<(), ErreurEcran> {
+        let gris = self.ecran.table_forme().is_some_and(TableForme::est_gris);
+        match format {
+            FormatImage::Pbm => fs::write(chemin, encoder_pbm(&self.affichage))?,
+            FormatImage::Ppm => fs::write(chemin, encoder_ppm(&self.affichage, gris))?,
+            #[cfg(feature = "image")]
+            FormatImage::Png => image::RgbImage::from_fn(
+                self.affichage.largeur() as u32,
+                self.affichage.hauteur() as u32,
+                |x, y| image::Rgb(couleur_rgb(&self.affichage, x as usize, y as usize, gris)),
+            )
+            .save_with_format(chemin, image::ImageFormat::Png)?,
+        }
+        Ok(())
+    }
+
+    /// Reconstruire l'image affichée à partir des commandes reçues par le contrôleur simulé et
+    /// l'enregistrer
+    fn actualiser_affichage(&mut self) -> Result<(), ErreurEcran> {
+        let ecran_complet = Zone::new(0, 0, M::LARGEUR, M::HAUTEUR);
+        let mut fenetre = ecran_complet;
+        let transmission = self.ecran.transmission();
+
+        for commande in self.controleur.commandes() {
+            match commande.instruction() {
+                Some(Instruction::PartialWindow) if commande.donnees.len() >= 8 => {
+                    let valeur = |i: usize| {
+                        (commande.donnees[i] as usize) << 8 | commande.donnees[i + 1] as usize
+                    };
+                    let (x, x_fin, y, y_fin) = (valeur(0) & !0x07, valeur(2), valeur(4), valeur(6));
+                    fenetre = Zone::new(x, y, x_fin + 1 - x, y_fin + 1 - y);
+                }
+                Some(Instruction::PartialOut) => fenetre = ecran_complet,
+                Some(Instruction::DisplayRefresh) => {
+                    self.affichage = self.memoire_controleur.clone();
+                    self.affichage.definir_rotation(self.ecran.rotation());
+                    self.affichage.definir_miroir(self.ecran.miroir());
+                    // Le plan rouge n'est pas affiché en noir et blanc
+                    if !transmission.iter().any(|plan| plan.plan == Plan::Rouge) {
+                        self.affichage.plan_rouge_mut().fill(0x00);
+                    }
+                }
+                Some(instruction) => {
+                    for transmission in transmission
+                        .iter()
+                        .filter(|transmission| transmission.instruction == instruction)
+                    {
+                        let mut octets: Vec<u8> = match transmission.inverse {
+                            true => commande.donnees.iter().map(|octet| !octet).collect(),
+                            false => commande.donnees.clone(),
+                        };
+                        // Le plan noir est transmis avant le plan combiné avec lui
+                        if transmission.ou_exclusif_noir {
+                            let (noir, _) = self.memoire_controleur.extraire_zone(&fenetre);
+                            octets.iter_mut().zip(noir).for_each(|(octet, noir)| *octet ^= noir);
+                        }
+                        self.memoire_controleur
+                            .ecrire_zone(&fenetre, transmission.plan, &octets);
+                    }
+                }
+                None => {}
+            }
+        }
+        self.controleur.effacer_journal();
+
+        self.enregistrer(&self.chemin, self.format)
+    }
+}
+
+/// Couleur RGB d'un pixel de la mémoire tampon, en couleur ou en 4 niveaux de gris
+fn couleur_rgb(memoire_tampon: &MemoireTampon, x: usize, y: usize, gris: bool) -> [u8; 3] {
+    if gris {
+        let luminance = memoire_tampon.gris(x, y).unwrap_or_default().luminance();
+        return [luminance; 3];
+    }
+    match memoire_tampon.pixel(x, y) {
+        Some(TriCouleur::Noir) => NOIR,
+        Some(TriCouleur::Rouge) => ROUGE,
+        _ => BLANC,
+    }
+}
+
+/// Pixels RGB (3 octets par pixel) de la mémoire tampon
+fn pixels_rgb(memoire_tampon: &MemoireTampon, gris: bool) -> Vec<u8> {
+    let mut pixels = Vec::with_capacity(memoire_tampon.largeur() * memoire_tampon.hauteur() * 3);
+    for y in 0..memoire_tampon.hauteur() {
+        for x in 0..memoire_tampon.largeur() {
+            pixels.extend_from_slice(&couleur_rgb(memoire_tampon, x, y, gris));
+        }
+    }
+    pixels
+}
+
+/// Encoder la mémoire tampon au format PBM binaire (1 pour un pixel noir ou rouge)
+fn encoder_pbm(memoire_tampon: &MemoireTampon) -> Vec<u8> {
+    let mut octets = format!(
+        "P4\n{} {}\n",
+        memoire_tampon.largeur(),
+        memoire_tampon.hauteur()
+    )
+    .into_bytes();
+    for y in 0..memoire_tampon.hauteur() {
+        let mut ligne = vec![0u8; memoire_tampon.largeur().div_ceil(8)];
+        for x in 0..memoire_tampon.largeur() {
+            if memoire_tampon.pixel(x, y) != Some(TriCouleur::Blanc) {
+                ligne[x / 8] |= 0x80 >> (x % 8);
+            }
+        }
+        octets.extend(ligne);
+    }
+    octets
+}
+
+/// Encoder la mémoire tampon au format PPM binaire
+fn encoder_ppm(memoire_tampon: &MemoireTampon, gris: bool) -> Vec<u8> {
+    let mut octets = format!(
+        "P6\n{} {}\n255\n",
+        memoire_tampon.largeur(),
+        memoire_tampon.hauteur()
+    )
+    .into_bytes();
+    octets.extend(pixels_rgb(memoire_tampon, gris));
+    octets
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::*;
+    use crate::ecran::{
+        memoire_tampon::Gris4,
+        modele::{Modele4In2B, Modele7In5V2},
+        rotation::Rotation,
+    };
+
+    /// Image de référence de la mire, régénérée si la variable d'environnement
+    /// `REGENERER_IMAGES_REFERENCE` est définie
+    const MIRE_4IN2B: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/images/mire_4in2b.pbm");
+
+    /// Mire en coordonnées logiques : cadre, diagonale, damier et rectangle rouge
+    fn dessiner_mire(memoire_tampon: &mut MemoireTampon) {
+        let (largeur, hauteur) = (memoire_tampon.largeur(), memoire_tampon.hauteur());
+        for y in 0..hauteur {
+            for x in 0..largeur {
+                let cadre = x < 4 || y < 4 || x >= largeur - 4 || y >= hauteur - 4;
+                let couleur = if cadre || x == y * largeur / hauteur {
+                    TriCouleur::Noir
+                } else if (20..100).contains(&x) && (20..100).contains(&y) {
+                    match (x / 10 + y / 10) % 2 {
+                        0 => TriCouleur::Noir,
+                        _ => TriCouleur::Blanc,
+                    }
+                } else if (150..250).contains(&x) && (300..350).contains(&y) {
+                    TriCouleur::Rouge
+                } else {
+                    TriCouleur::Blanc
+                };
+                memoire_tampon.definir_pixel(x, y, couleur);
+            }
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn image_reference() {
+        let chemin = env::temp_dir().join(format!("mire_4in2b_{}.pbm", std::process::id()));
+        let mut ecran: EcranVirtuel<Modele4In2B> = EcranVirtuel::new(&chemin).unwrap();
+        ecran.definir_rotation(Rotation::R90);
+        dessiner_mire(ecran.memoire_tampon_mut());
+
+        assert_eq!(ecran.mettre_a_jour().await.unwrap(), MiseAJour::Effectuee);
+        assert_eq!(ecran.affichage().largeur(), 300);
+        assert_eq!(ecran.affichage().pixel(200, 320), Some(TriCouleur::Rouge));
+
+        let image = fs::read(&chemin).unwrap();
+        fs::remove_file(&chemin).unwrap();
+        if env::var_os("REGENERER_IMAGES_REFERENCE").is_some() {
+            fs::write(MIRE_4IN2B, &image).unwrap();
+        }
+        assert!(
+            image == fs::read(MIRE_4IN2B).unwrap(),
+            "image différente de {MIRE_4IN2B}"
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn mise_a_jour_zone_reconstruite() {
+        let chemin = env::temp_dir().join(format!("zone_7in5b_{}.pbm", std::process::id()));
+        let mut ecran: EcranVirtuel = EcranVirtuel::new(&chemin).unwrap();
+        ecran.forcer_mise_a_jour().await.unwrap();
+
+        ecran.memoire_tampon_mut().effacer(TriCouleur::Noir);
+        let zone = Zone::new(13, 7, 20, 5);
+        ecran.mettre_a_jour_zone(zone).await.unwrap();
+        fs::remove_file(&chemin).unwrap();
+
+        // Seule la fenêtre élargie aux multiples de 8 pixels est affichée en noir
+        let noirs: Vec<(usize, usize)> = (0..ecran.hauteur())
+            .flat_map(|y| (0..ecran.largeur()).map(move |x| (x, y)))
+            .filter(|&(x, y)| ecran.affichage().pixel(x, y) == Some(TriCouleur::Noir))
+            .collect();
+        assert_eq!(noirs.len(), 32 * 5);
+        assert!(noirs
+            .iter()
+            .all(|&(x, y)| (8..40).contains(&x) && (7..12).contains(&y)));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn gris_4_niveaux_reconstruits() {
+        let chemin = env::temp_dir().join(format!("gris_7in5_{}.ppm", std::process::id()));
+        let mut ecran: EcranVirtuel<Modele7In5V2> = EcranVirtuel::new(&chemin).unwrap();
+        ecran.definir_table_forme(Some(TableForme::gris_4_niveaux()));
+        let niveaux = [
+            Gris4::Blanc,
+            Gris4::GrisClair,
+            Gris4::GrisFonce,
+            Gris4::Noir,
+        ];
+        for y in 0..ecran.hauteur() {
+            for x in 0..ecran.largeur() {
+                ecran
+                    .memoire_tampon_mut()
+                    .definir_gris(x, y, niveaux[(x + y) % 4]);
+            }
+        }
+
+        ecran.mettre_a_jour().await.unwrap();
+        let zone = Zone::new(16, 8, 24, 4);
+        for y in 8..12 {
+            for x in 16..40 {
+                ecran
+                    .memoire_tampon_mut()
+                    .definir_gris(x, y, Gris4::GrisFonce);
+            }
+        }
+        ecran.mettre_a_jour_zone(zone).await.unwrap();
+        fs::remove_file(&chemin).unwrap();
+
+        // Le plan rouge est retrouvé à partir du plan noir transmis avant lui
+        assert_eq!(
+            ecran.affichage().plan_noir(),
+            ecran.memoire_tampon().plan_noir()
+        );
+        assert_eq!(
+            ecran.affichage().plan_rouge(),
+            ecran.memoire_tampon().plan_rouge()
+        );
+    }
+}
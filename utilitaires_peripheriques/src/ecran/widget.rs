@@ -0,0 +1,892 @@
+use std::fmt::{self, Write};
+
+use chrono::{
+    format::{Item, StrftimeItems},
+    Datelike, Months, NaiveDate, NaiveDateTime,
+};
+
+use crate::ecran::{
+    erreur::ErreurEcran,
+    memoire_tampon::{MemoireTampon, TriCouleur},
+    texte::{Alignement, AlignementVertical, Polices, StyleTexte},
+    zone::Zone,
+};
+
+/// Noms des mois affichés par le calendrier
+const MOIS: [&str; 12] = [
+    "janvier",
+    "février",
+    "mars",
+    "avril",
+    "mai",
+    "juin",
+    "juillet",
+    "août",
+    "septembre",
+    "octobre",
+    "novembre",
+    "décembre",
+];
+
+/// Initiales des jours de la semaine affichées par le calendrier, du lundi au dimanche
+const JOURS: [&str; 7] = ["L", "M", "M", "J", "V", "S", "D"];
+
+/// Informations communes à tous les widgets lors du dessin
+pub struct Contexte<'a> {
+    /// Polices de caractères
+    pub polices: &'a Polices,
+    /// Date et heure affichées par les horloges et les calendriers
+    pub maintenant: NaiveDateTime,
+}
+
+impl<'a> Contexte<'a> {
+    /// Contexte de dessin à une date donnée
+    pub fn new(polices: &'a Polices, maintenant: NaiveDateTime) -> Self {
+        Self {
+            polices,
+            maintenant,
+        }
+    }
+}
+
+/// Elément d'un tableau de bord dessiné dans la mémoire tampon
+pub trait Widget {
+    /// Dimensions (largeur, hauteur) souhaitées dans l'espace disponible
+    fn mesurer(&self, contexte: &Contexte, largeur: usize, hauteur: usize) -> (usize, usize);
+
+    /// Dessiner dans une zone de la mémoire tampon, en coordonnées logiques
+    fn dessiner(&self, contexte: &Contexte, memoire_tampon: &mut MemoireTampon, zone: Zone);
+
+    /// Dessiner sur toute la mémoire tampon
+    fn afficher(&self, contexte: &Contexte, memoire_tampon: &mut MemoireTampon) {
+        let zone = Zone::new(0, 0, memoire_tampon.largeur(), memoire_tampon.hauteur());
+        self.dessiner(contexte, memoire_tampon, zone);
+    }
+}
+
+impl<W: Widget + ?Sized> Widget for Box<W> {
+    fn mesurer(&self, contexte: &Contexte, largeur: usize, hauteur: usize) -> (usize, usize) {
+        (**self).mesurer(contexte, largeur, hauteur)
+    }
+
+    fn dessiner(&self, contexte: &Contexte, memoire_tampon: &mut MemoireTampon, zone: Zone) {
+        (**self).dessiner(contexte, memoire_tampon, zone)
+    }
+}
+
+/// Remplir une zone d'une couleur
+pub(crate) fn remplir(memoire_tampon: &mut MemoireTampon, zone: Zone, couleur: TriCouleur) {
+    for y in zone.y..zone.y + zone.hauteur {
+        for x in zone.x..zone.x + zone.largeur {
+            memoire_tampon.definir_pixel(x, y, couleur);
+        }
+    }
+}
+
+/// Vérifier un format de date avec la syntaxe de [`chrono::format::strftime`]
+pub(crate) fn verifier_format_date(format: &str) -> Result<(), ErreurEcran> {
+    match StrftimeItems::new(format).any(|item| item == Item::Error) {
+        true => Err(ErreurEcran::FormatDateInvalide(format.to_string())),
+        false => Ok(()),
+    }
+}
+
+/// Texte d'une date mise en forme par chrono, ou `None` si le format est invalide ou demande
+/// une information absente de la date, comme le fuseau horaire d'une date sans fuseau
+pub(crate) fn ecrire_date(date: impl fmt::Display) -> Option<String> {
+    let mut texte = String::new();
+    match write!(texte, "{date}") {
+        Ok(()) => Some(texte),
+        Err(fmt::Error) => {
+            log::warn!("Date impossible à mettre en forme");
+            None
+        }
+    }
+}
+
+/// Dimensions d'un texte disposé dans l'espace disponible
+fn mesurer_texte(
+    polices: &Polices,
+    texte: &str,
+    style: &StyleTexte,
+    largeur: usize,
+    hauteur: usize,
+) -> (usize, usize) {
+    let disposition = polices.disposer(texte, style, largeur as f32, hauteur as f32);
+    let metriques = polices.metriques(style.taille);
+    let largeur_texte = disposition
+        .lignes
+        .iter()
+        .map(|ligne| ligne.largeur)
+        .fold(0., f32::max);
+    let hauteur_texte = match disposition.lignes.len() {
+        0 => 0.,
+        n => {
+            (n - 1) as f32 * metriques.hauteur_ligne() * style.interligne + metriques.ascendante
+                - metriques.descendante
+        }
+    };
+    (
+        (largeur_texte.ceil() as usize).min(largeur),
+        (hauteur_texte.ceil() as usize).min(hauteur),
+    )
+}
+
+/// Texte fixe
+pub struct Texte {
+    texte: String,
+    style: StyleTexte,
+}
+
+impl Texte {
+    /// Texte affiché avec un style
+    pub fn new(texte: impl Into<String>, style: StyleTexte) -> Self {
+        Self {
+            texte: texte.into(),
+            style,
+        }
+    }
+}
+
+impl Widget for Texte {
+    fn mesurer(&self, contexte: &Contexte, largeur: usize, hauteur: usize) -> (usize, usize) {
+        mesurer_texte(contexte.polices, &self.texte, &self.style, largeur, hauteur)
+    }
+
+    fn dessiner(&self, contexte: &Contexte, memoire_tampon: &mut MemoireTampon, zone: Zone) {
+        contexte
+            .polices
+            .dessiner(memoire_tampon, zone, &self.texte, &self.style);
+    }
+}
+
+/// Date ou heure du contexte, mise en forme avec la syntaxe de [`chrono::format::strftime`]
+pub struct Horloge {
+    format: String,
+    style: StyleTexte,
+}
+
+impl Horloge {
+    /// Horloge mise en forme, par exemple `%H:%M` ou `%d/%m/%Y`.
+    /// Renvoie [`ErreurEcran::FormatDateInvalide`] si le format n'est pas reconnu.
+    pub fn new(format: impl Into<String>, style: StyleTexte) -> Result<Self, ErreurEcran> {
+        let format = format.into();
+        verifier_format_date(&format)?;
+        Ok(Self { format, style })
+    }
+
+    /// Date mise en forme, vide si le format demande le fuseau horaire
+    fn texte(&self, contexte: &Contexte) -> String {
+        ecrire_date(contexte.maintenant.format(&self.format)).unwrap_or_default()
+    }
+}
+
+impl Widget for Horloge {
+    fn mesurer(&self, contexte: &Contexte, largeur: usize, hauteur: usize) -> (usize, usize) {
+        mesurer_texte(contexte.polices, &self.texte(contexte), &self.style, largeur, hauteur)
+    }
+
+    fn dessiner(&self, contexte: &Contexte, memoire_tampon: &mut MemoireTampon, zone: Zone) {
+        contexte
+            .polices
+            .dessiner(memoire_tampon, zone, &self.texte(contexte), &self.style);
+    }
+}
+
+/// Calendrier du mois de la date du contexte, la semaine commençant le lundi
+pub struct Calendrier {
+    style: StyleTexte,
+    couleur_aujourdhui: TriCouleur,
+}
+
+impl Calendrier {
+    /// Calendrier dont le jour courant est affiché en blanc sur fond rouge
+    pub fn new(style: StyleTexte) -> Self {
+        Self {
+            style,
+            couleur_aujourdhui: TriCouleur::Rouge,
+        }
+    }
+
+    /// Choisir la couleur du fond du jour courant
+    pub fn avec_couleur_aujourdhui(mut self, couleur_aujourdhui: TriCouleur) -> Self {
+        self.couleur_aujourdhui = couleur_aujourdhui;
+        self
+    }
+
+    /// Décalage du premier jour du mois dans la semaine et nombre de jours du mois
+    fn mois(aujourdhui: NaiveDate) -> (usize, u32) {
+        let premier = aujourdhui.with_day(1).unwrap_or(aujourdhui);
+        let nombre_jours = premier
+            .checked_add_months(Months::new(1))
+            .and_then(|suivant| suivant.pred_opt())
+            .map(|dernier| dernier.day())
+            .unwrap_or(31);
+        (premier.weekday().num_days_from_monday() as usize, nombre_jours)
+    }
+}
+
+impl Widget for Calendrier {
+    fn mesurer(&self, _contexte: &Contexte, largeur: usize, hauteur: usize) -> (usize, usize) {
+        (largeur, hauteur)
+    }
+
+    fn dessiner(&self, contexte: &Contexte, memoire_tampon: &mut MemoireTampon, zone: Zone) {
+        let aujourdhui = contexte.maintenant.date();
+        let (decalage, nombre_jours) = Self::mois(aujourdhui);
+        let semaines = (decalage + nombre_jours as usize).div_ceil(7);
+        let largeur_case = zone.largeur / 7;
+        let hauteur_case = zone.hauteur / (semaines + 2);
+        if largeur_case == 0 || hauteur_case == 0 {
+            return;
+        }
+        let style = self
+            .style
+            .avec_alignement(Alignement::Centre)
+            .avec_alignement_vertical(AlignementVertical::Milieu);
+        let case = |colonne: usize, ligne: usize| {
+            Zone::new(
+                zone.x + colonne * largeur_case,
+                zone.y + ligne * hauteur_case,
+                largeur_case,
+                hauteur_case,
+            )
+        };
+
+        let titre = format!("{} {}", MOIS[aujourdhui.month0() as usize], aujourdhui.year());
+        let zone_titre = Zone::new(zone.x, zone.y, largeur_case * 7, hauteur_case);
+        contexte
+            .polices
+            .dessiner(memoire_tampon, zone_titre, &titre, &style);
+        for (colonne, jour) in JOURS.iter().enumerate() {
+            contexte
+                .polices
+                .dessiner(memoire_tampon, case(colonne, 1), jour, &style);
+        }
+
+        for jour in 1..=nombre_jours {
+            let position = decalage + jour as usize - 1;
+            let zone_jour = case(position % 7, position / 7 + 2);
+            let style_jour = match jour == aujourdhui.day() {
+                true => {
+                    remplir(memoire_tampon, zone_jour, self.couleur_aujourdhui);
+                    style.avec_couleur(TriCouleur::Blanc)
+                }
+                false => style,
+            };
+            contexte
+                .polices
+                .dessiner(memoire_tampon, zone_jour, &jour.to_string(), &style_jour);
+        }
+    }
+}
+
+/// Valeur mesurée par un capteur avec son libellé et son unité
+pub struct ValeurCapteur {
+    libelle: String,
+    valeur: Option<f64>,
+    unite: String,
+    decimales: usize,
+    style_libelle: StyleTexte,
+    style_valeur: StyleTexte,
+}
+
+impl ValeurCapteur {
+    /// Valeur affichée avec deux décimales sous son libellé, « — » si elle est inconnue
+    pub fn new(libelle: impl Into<String>, valeur: Option<f64>, unite: impl Into<String>) -> Self {
+        Self {
+            libelle: libelle.into(),
+            valeur,
+            unite: unite.into(),
+            decimales: 2,
+            style_libelle: StyleTexte::new(24.).avec_alignement(Alignement::Centre),
+            style_valeur: StyleTexte::new(60.)
+                .avec_alignement(Alignement::Centre)
+                .avec_alignement_vertical(AlignementVertical::Milieu),
+        }
+    }
+
+    /// Choisir le nombre de décimales affichées
+    pub fn avec_decimales(mut self, decimales: usize) -> Self {
+        self.decimales = decimales;
+        self
+    }
+
+    /// Choisir les styles du libellé et de la valeur
+    pub fn avec_styles(mut self, style_libelle: StyleTexte, style_valeur: StyleTexte) -> Self {
+        self.style_libelle = style_libelle;
+        self.style_valeur = style_valeur;
+        self
+    }
+
+    /// Choisir le style du libellé
+    pub fn avec_style_libelle(mut self, style_libelle: StyleTexte) -> Self {
+        self.style_libelle = style_libelle;
+        self
+    }
+
+    /// Choisir le style de la valeur
+    pub fn avec_style_valeur(mut self, style_valeur: StyleTexte) -> Self {
+        self.style_valeur = style_valeur;
+        self
+    }
+
+    fn texte_valeur(&self) -> String {
+        match self.valeur {
+            Some(valeur) => format!("{valeur:.*} {}", self.decimales, self.unite),
+            None => format!("— {}", self.unite),
+        }
+    }
+
+    /// Hauteur réservée au libellé
+    fn hauteur_libelle(&self, contexte: &Contexte) -> usize {
+        match self.libelle.is_empty() {
+            true => 0,
+            false => {
+                let metriques = contexte.polices.metriques(self.style_libelle.taille);
+                (metriques.ascendante - metriques.descendante).ceil() as usize
+            }
+        }
+    }
+}
+
+impl Widget for ValeurCapteur {
+    fn mesurer(&self, contexte: &Contexte, largeur: usize, hauteur: usize) -> (usize, usize) {
+        let hauteur_libelle = self.hauteur_libelle(contexte).min(hauteur);
+        let (largeur_libelle, _) = mesurer_texte(
+            contexte.polices,
+            &self.libelle,
+            &self.style_libelle,
+            largeur,
+            hauteur_libelle,
+        );
+        let (largeur_valeur, hauteur_valeur) = mesurer_texte(
+            contexte.polices,
+            &self.texte_valeur(),
+            &self.style_valeur,
+            largeur,
+            hauteur - hauteur_libelle,
+        );
+        (
+            largeur_libelle.max(largeur_valeur),
+            hauteur_libelle + hauteur_valeur,
+        )
+    }
+
+    fn dessiner(&self, contexte: &Contexte, memoire_tampon: &mut MemoireTampon, zone: Zone) {
+        let hauteur_libelle = self.hauteur_libelle(contexte).min(zone.hauteur);
+        let zone_libelle = Zone::new(zone.x, zone.y, zone.largeur, hauteur_libelle);
+        let zone_valeur = Zone::new(
+            zone.x,
+            zone.y + hauteur_libelle,
+            zone.largeur,
+            zone.hauteur - hauteur_libelle,
+        );
+        contexte
+            .polices
+            .dessiner(memoire_tampon, zone_libelle, &self.libelle, &self.style_libelle);
+        contexte.polices.dessiner(
+            memoire_tampon,
+            zone_valeur,
+            &self.texte_valeur(),
+            &self.style_valeur,
+        );
+    }
+}
+
+/// Icône monochrome centrée dans sa zone
+///
+/// Les pixels sont organisés comme le plan noir de la mémoire tampon : un bit par pixel,
+/// lignes complétées à l'octet, 1 pour un pixel dessiné dans la couleur de l'icône.
+pub struct Icone {
+    largeur: usize,
+    hauteur: usize,
+    pixels: Vec<u8>,
+    couleur: TriCouleur,
+    echelle: usize,
+}
+
+impl Icone {
+    /// Icône noire à l'échelle 1
+    pub fn new(largeur: usize, hauteur: usize, pixels: Vec<u8>) -> Result<Self, ErreurEcran> {
+        let attendue = largeur.div_ceil(8) * hauteur;
+        if pixels.len() != attendue {
+            return Err(ErreurEcran::TailleImageInvalide {
+                attendue,
+                recue: pixels.len(),
+            });
+        }
+        Ok(Self {
+            largeur,
+            hauteur,
+            pixels,
+            couleur: TriCouleur::Noir,
+            echelle: 1,
+        })
+    }
+
+    /// Choisir la couleur de l'icône
+    pub fn avec_couleur(mut self, couleur: TriCouleur) -> Self {
+        self.couleur = couleur;
+        self
+    }
+
+    /// Agrandir l'icône d'un facteur entier
+    pub fn avec_echelle(mut self, echelle: usize) -> Self {
+        self.echelle = echelle.max(1);
+        self
+    }
+
+    fn est_dessine(&self, x: usize, y: usize) -> bool {
+        self.pixels[y * self.largeur.div_ceil(8) + x / 8] & (0x80 >> (x % 8)) != 0
+    }
+}
+
+impl Widget for Icone {
+    fn mesurer(&self, _contexte: &Contexte, largeur: usize, hauteur: usize) -> (usize, usize) {
+        (
+            (self.largeur * self.echelle).min(largeur),
+            (self.hauteur * self.echelle).min(hauteur),
+        )
+    }
+
+    fn dessiner(&self, _contexte: &Contexte, memoire_tampon: &mut MemoireTampon, zone: Zone) {
+        let largeur = self.largeur * self.echelle;
+        let hauteur = self.hauteur * self.echelle;
+        let x0 = zone.x + zone.largeur.saturating_sub(largeur) / 2;
+        let y0 = zone.y + zone.hauteur.saturating_sub(hauteur) / 2;
+        for y in 0..hauteur.min(zone.hauteur) {
+            for x in 0..largeur.min(zone.largeur) {
+                if self.est_dessine(x / self.echelle, y / self.echelle) {
+                    memoire_tampon.definir_pixel(x0 + x, y0 + y, self.couleur);
+                }
+            }
+        }
+    }
+}
+
+/// Hauteur d'un élément d'une colonne
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "mise_en_page",
+    derive(serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum Dimension {
+    /// Hauteur mesurée par l'élément
+    Auto,
+    /// Hauteur fixe en pixels
+    Fixe(usize),
+    /// Part de la hauteur restante, proportionnelle au poids
+    Proportion(usize),
+}
+
+/// Eléments empilés verticalement
+#[derive(Default)]
+pub struct Colonne {
+    enfants: Vec<(Box<dyn Widget>, Dimension)>,
+    espacement: usize,
+}
+
+impl Colonne {
+    /// Colonne vide sans espacement
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ajouter un élément
+    pub fn avec(mut self, widget: impl Widget + 'static, dimension: Dimension) -> Self {
+        self.enfants.push((Box::new(widget), dimension));
+        self
+    }
+
+    /// Choisir l'espacement vertical entre les éléments
+    pub fn avec_espacement(mut self, espacement: usize) -> Self {
+        self.espacement = espacement;
+        self
+    }
+
+    /// Hauteur attribuée à chaque élément
+    fn repartir(&self, contexte: &Contexte, largeur: usize, hauteur: usize) -> Vec<usize> {
+        let espacements = self.espacement * self.enfants.len().saturating_sub(1);
+        let mut restante = hauteur.saturating_sub(espacements);
+        let mut hauteurs: Vec<usize> = self
+            .enfants
+            .iter()
+            .map(|(widget, dimension)| {
+                let hauteur = match dimension {
+                    Dimension::Auto => widget.mesurer(contexte, largeur, restante).1,
+                    Dimension::Fixe(hauteur) => (*hauteur).min(restante),
+                    Dimension::Proportion(_) => 0,
+                };
+                restante -= hauteur;
+                hauteur
+            })
+            .collect();
+
+        let poids: usize = self
+            .enfants
+            .iter()
+            .map(|(_, dimension)| match dimension {
+                Dimension::Proportion(poids) => *poids,
+                _ => 0,
+            })
+            .sum();
+        for (hauteur, (_, dimension)) in hauteurs.iter_mut().zip(&self.enfants) {
+            if let Dimension::Proportion(p) = dimension {
+                *hauteur = (restante * p).checked_div(poids).unwrap_or(0);
+            }
+        }
+        hauteurs
+    }
+}
+
+impl Widget for Colonne {
+    fn mesurer(&self, contexte: &Contexte, largeur: usize, hauteur: usize) -> (usize, usize) {
+        let hauteurs = self.repartir(contexte, largeur, hauteur);
+        let largeur_maximale = self
+            .enfants
+            .iter()
+            .zip(&hauteurs)
+            .map(|((widget, _), hauteur)| widget.mesurer(contexte, largeur, *hauteur).0)
+            .max()
+            .unwrap_or(0);
+        let espacements = self.espacement * self.enfants.len().saturating_sub(1);
+        (largeur_maximale, (hauteurs.iter().sum::<usize>() + espacements).min(hauteur))
+    }
+
+    fn dessiner(&self, contexte: &Contexte, memoire_tampon: &mut MemoireTampon, zone: Zone) {
+        let hauteurs = self.repartir(contexte, zone.largeur, zone.hauteur);
+        let mut y = zone.y;
+        for ((widget, _), hauteur) in self.enfants.iter().zip(hauteurs) {
+            widget.dessiner(
+                contexte,
+                memoire_tampon,
+                Zone::new(zone.x, y, zone.largeur, hauteur),
+            );
+            y += hauteur + self.espacement;
+        }
+    }
+}
+
+/// Eléments disposés dans des cases de mêmes dimensions, ligne par ligne
+pub struct Grille {
+    colonnes: usize,
+    enfants: Vec<Box<dyn Widget>>,
+    espacement: usize,
+}
+
+impl Grille {
+    /// Grille vide d'un nombre de colonnes donné, sans espacement
+    pub fn new(colonnes: usize) -> Self {
+        Self {
+            colonnes: colonnes.max(1),
+            enfants: Vec::new(),
+            espacement: 0,
+        }
+    }
+
+    /// Ajouter un élément dans la case suivante
+    pub fn avec(mut self, widget: impl Widget + 'static) -> Self {
+        self.enfants.push(Box::new(widget));
+        self
+    }
+
+    /// Choisir l'espacement entre les cases
+    pub fn avec_espacement(mut self, espacement: usize) -> Self {
+        self.espacement = espacement;
+        self
+    }
+}
+
+impl Widget for Grille {
+    fn mesurer(&self, _contexte: &Contexte, largeur: usize, hauteur: usize) -> (usize, usize) {
+        (largeur, hauteur)
+    }
+
+    fn dessiner(&self, contexte: &Contexte, memoire_tampon: &mut MemoireTampon, zone: Zone) {
+        let lignes = self.enfants.len().div_ceil(self.colonnes);
+        if lignes == 0 {
+            return;
+        }
+        let largeur_case = zone
+            .largeur
+            .saturating_sub(self.espacement * (self.colonnes - 1))
+            / self.colonnes;
+        let hauteur_case = zone.hauteur.saturating_sub(self.espacement * (lignes - 1)) / lignes;
+        for (i, widget) in self.enfants.iter().enumerate() {
+            let case = Zone::new(
+                zone.x + (i % self.colonnes) * (largeur_case + self.espacement),
+                zone.y + (i / self.colonnes) * (hauteur_case + self.espacement),
+                largeur_case,
+                hauteur_case,
+            );
+            widget.dessiner(contexte, memoire_tampon, case);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use super::*;
+
+    fn polices() -> Polices {
+        Polices::depuis_fichier(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/polices/DejaVuSansMono.ttf"
+        ))
+        .unwrap()
+    }
+
+    fn maintenant() -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2024, 2, 29)
+            .unwrap()
+            .and_hms_opt(7, 5, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn horloge() {
+        let polices = polices();
+        let contexte = Contexte::new(&polices, maintenant());
+        let horloge = Horloge::new("%d/%m/%Y %H:%M", StyleTexte::default()).unwrap();
+        assert_eq!(horloge.texte(&contexte), "29/02/2024 07:05");
+    }
+
+    #[test]
+    fn horloge_format_invalide() {
+        for format in ["%Q", "%", "%H:%"] {
+            assert!(matches!(
+                Horloge::new(format, StyleTexte::default()),
+                Err(ErreurEcran::FormatDateInvalide(invalide)) if invalide == format
+            ));
+        }
+    }
+
+    #[test]
+    fn horloge_sans_fuseau_horaire() {
+        // Format valide mais impossible à appliquer à une date sans fuseau horaire
+        let polices = polices();
+        let contexte = Contexte::new(&polices, maintenant());
+        let horloge = Horloge::new("%H:%M %Z", StyleTexte::default()).unwrap();
+        assert_eq!(horloge.texte(&contexte), "");
+        let mut memoire_tampon = MemoireTampon::new(100, 50);
+        horloge.afficher(&contexte, &mut memoire_tampon);
+    }
+
+    /// Widget de taille fixe qui enregistre les zones dans lesquelles il est dessiné
+    struct Enregistreur {
+        largeur: usize,
+        hauteur: usize,
+        zones: Rc<RefCell<Vec<Zone>>>,
+    }
+
+    impl Enregistreur {
+        fn new(largeur: usize, hauteur: usize, zones: &Rc<RefCell<Vec<Zone>>>) -> Self {
+            Self {
+                largeur,
+                hauteur,
+                zones: zones.clone(),
+            }
+        }
+    }
+
+    impl Widget for Enregistreur {
+        fn mesurer(&self, _contexte: &Contexte, largeur: usize, hauteur: usize) -> (usize, usize) {
+            (self.largeur.min(largeur), self.hauteur.min(hauteur))
+        }
+
+        fn dessiner(&self, _contexte: &Contexte, _memoire_tampon: &mut MemoireTampon, zone: Zone) {
+            self.zones.borrow_mut().push(zone);
+        }
+    }
+
+    /// Coordonnées des pixels d'une couleur
+    fn pixels(memoire_tampon: &MemoireTampon, couleur: TriCouleur) -> Vec<(usize, usize)> {
+        (0..memoire_tampon.hauteur())
+            .flat_map(|y| (0..memoire_tampon.largeur()).map(move |x| (x, y)))
+            .filter(|(x, y)| memoire_tampon.pixel(*x, *y) == Some(couleur))
+            .collect()
+    }
+
+    #[test]
+    fn colonne_repartition() {
+        let polices = polices();
+        let contexte = Contexte::new(&polices, maintenant());
+        let zones = Rc::new(RefCell::new(Vec::new()));
+        let colonne = Colonne::new()
+            .avec(Enregistreur::new(10, 5, &zones), Dimension::Fixe(20))
+            .avec(Enregistreur::new(30, 15, &zones), Dimension::Auto)
+            .avec(Enregistreur::new(10, 5, &zones), Dimension::Proportion(1))
+            .avec(Enregistreur::new(10, 5, &zones), Dimension::Proportion(3))
+            .avec_espacement(2);
+
+        // 100 pixels moins 3 espacements : 20 fixes, 15 mesurés, puis 59 répartis en 1/4 et 3/4
+        let mut memoire_tampon = MemoireTampon::new(60, 120);
+        colonne.dessiner(&contexte, &mut memoire_tampon, Zone::new(5, 10, 40, 100));
+        assert_eq!(
+            *zones.borrow(),
+            [
+                Zone::new(5, 10, 40, 20),
+                Zone::new(5, 32, 40, 15),
+                Zone::new(5, 49, 40, 14),
+                Zone::new(5, 65, 40, 44),
+            ]
+        );
+        assert_eq!(colonne.mesurer(&contexte, 40, 100), (30, 99));
+    }
+
+    #[test]
+    fn colonne_debordement() {
+        let polices = polices();
+        let contexte = Contexte::new(&polices, maintenant());
+        let zones = Rc::new(RefCell::new(Vec::new()));
+        let colonne = Colonne::new()
+            .avec(Enregistreur::new(10, 5, &zones), Dimension::Fixe(20))
+            .avec(Enregistreur::new(10, 50, &zones), Dimension::Auto)
+            .avec(Enregistreur::new(10, 5, &zones), Dimension::Fixe(20))
+            .avec(Enregistreur::new(10, 5, &zones), Dimension::Proportion(1));
+
+        // Les éléments qui ne tiennent plus sont réduits, jusqu'à une hauteur nulle
+        let mut memoire_tampon = MemoireTampon::new(10, 30);
+        colonne.afficher(&contexte, &mut memoire_tampon);
+        assert_eq!(
+            *zones.borrow(),
+            [
+                Zone::new(0, 0, 10, 20),
+                Zone::new(0, 20, 10, 10),
+                Zone::new(0, 30, 10, 0),
+                Zone::new(0, 30, 10, 0),
+            ]
+        );
+        assert_eq!(colonne.mesurer(&contexte, 10, 30), (10, 30));
+    }
+
+    #[test]
+    fn grille_cases() {
+        let polices = polices();
+        let contexte = Contexte::new(&polices, maintenant());
+        let zones = Rc::new(RefCell::new(Vec::new()));
+        let grille = (0..5).fold(Grille::new(3).avec_espacement(2), |grille, _| {
+            grille.avec(Enregistreur::new(1, 1, &zones))
+        });
+
+        // 3 colonnes de 9 pixels et 2 lignes de 9 pixels, séparées de 2 pixels
+        let mut memoire_tampon = MemoireTampon::new(40, 30);
+        grille.dessiner(&contexte, &mut memoire_tampon, Zone::new(4, 6, 32, 20));
+        assert_eq!(
+            *zones.borrow(),
+            [
+                Zone::new(4, 6, 9, 9),
+                Zone::new(15, 6, 9, 9),
+                Zone::new(26, 6, 9, 9),
+                Zone::new(4, 17, 9, 9),
+                Zone::new(15, 17, 9, 9),
+            ]
+        );
+    }
+
+    #[test]
+    fn grille_debordement() {
+        let polices = polices();
+        let contexte = Contexte::new(&polices, maintenant());
+        let icone = || Icone::new(1, 1, vec![0x80]).unwrap().avec_echelle(4);
+        let grille = (0..4).fold(Grille::new(4).avec_espacement(3), |grille, _| {
+            grille.avec(icone())
+        });
+
+        // Les espacements prennent toute la largeur : les cases sont vides et rien n'est dessiné
+        let mut memoire_tampon = MemoireTampon::new(16, 8);
+        grille.dessiner(&contexte, &mut memoire_tampon, Zone::new(0, 0, 8, 8));
+        assert!(pixels(&memoire_tampon, TriCouleur::Noir).is_empty());
+
+        // Grille vide
+        Grille::new(2).afficher(&contexte, &mut memoire_tampon);
+        assert!(pixels(&memoire_tampon, TriCouleur::Noir).is_empty());
+    }
+
+    #[test]
+    fn icone_agrandie() {
+        let polices = polices();
+        let contexte = Contexte::new(&polices, maintenant());
+        // 3×2 pixels : coins supérieurs et milieu inférieur
+        let icone = Icone::new(3, 2, vec![0b1010_0000, 0b0100_0000])
+            .unwrap()
+            .avec_echelle(2)
+            .avec_couleur(TriCouleur::Rouge);
+        assert_eq!(icone.mesurer(&contexte, 20, 20), (6, 4));
+        assert_eq!(icone.mesurer(&contexte, 5, 3), (5, 3));
+
+        // 6×4 pixels centrés dans une zone de 10×10 en (10, 10)
+        let mut memoire_tampon = MemoireTampon::new(24, 24);
+        icone.dessiner(&contexte, &mut memoire_tampon, Zone::new(10, 10, 10, 10));
+        let carre = |x: usize, y: usize| [(x, y), (x + 1, y), (x, y + 1), (x + 1, y + 1)];
+        let mut attendus: Vec<_> = [carre(12, 13), carre(16, 13), carre(14, 15)]
+            .concat()
+            .into_iter()
+            .collect();
+        attendus.sort_by_key(|(x, y)| (*y, *x));
+        assert_eq!(pixels(&memoire_tampon, TriCouleur::Rouge), attendus);
+    }
+
+    #[test]
+    fn icone_rognee() {
+        let polices = polices();
+        let contexte = Contexte::new(&polices, maintenant());
+        let icone = Icone::new(3, 2, vec![0b1010_0000, 0b0100_0000])
+            .unwrap()
+            .avec_echelle(2);
+
+        // Zone plus petite que l'icône : seul le coin supérieur gauche est dessiné
+        let mut memoire_tampon = MemoireTampon::new(8, 8);
+        icone.dessiner(&contexte, &mut memoire_tampon, Zone::new(1, 1, 3, 3));
+        assert_eq!(
+            pixels(&memoire_tampon, TriCouleur::Noir),
+            [(1, 1), (2, 1), (1, 2), (2, 2), (3, 3)]
+        );
+    }
+
+    #[test]
+    fn calendrier_jour_courant() {
+        let polices = polices();
+        let contexte = Contexte::new(&polices, maintenant());
+        let calendrier = Calendrier::new(StyleTexte::new(8.));
+
+        // Février 2024 commence un jeudi et compte 29 jours : titre, jours et 5 semaines,
+        // soit 7 lignes de 11 pixels et 7 colonnes de 10 pixels
+        let mut memoire_tampon = MemoireTampon::new(80, 90);
+        calendrier.dessiner(&contexte, &mut memoire_tampon, Zone::new(5, 3, 70, 80));
+        let aujourdhui = Zone::new(5 + 3 * 10, 3 + 6 * 11, 10, 11);
+        let rouges = pixels(&memoire_tampon, TriCouleur::Rouge);
+        assert!(rouges.contains(&(aujourdhui.x, aujourdhui.y)));
+        assert!(rouges.contains(&(aujourdhui.x + 9, aujourdhui.y + 10)));
+        assert!(rouges.iter().all(|(x, y)| {
+            (aujourdhui.x..aujourdhui.x + 10).contains(x)
+                && (aujourdhui.y..aujourdhui.y + 11).contains(y)
+        }));
+        // Numéro du jour écrit en blanc sur le fond rouge
+        assert!(rouges.len() < 10 * 11);
+
+        // Texte noir dans la zone du calendrier uniquement
+        let noirs = pixels(&memoire_tampon, TriCouleur::Noir);
+        assert!(!noirs.is_empty());
+        assert!(noirs
+            .iter()
+            .all(|(x, y)| (5..75).contains(x) && (3..83).contains(y)));
+    }
+
+    #[test]
+    fn calendrier_trop_petit() {
+        let polices = polices();
+        let contexte = Contexte::new(&polices, maintenant());
+        let calendrier = Calendrier::new(StyleTexte::new(8.));
+
+        // Moins d'un pixel par jour de la semaine : rien n'est dessiné
+        let mut memoire_tampon = MemoireTampon::new(80, 90);
+        calendrier.dessiner(&contexte, &mut memoire_tampon, Zone::new(0, 0, 6, 80));
+        assert!(pixels(&memoire_tampon, TriCouleur::Noir).is_empty());
+        assert!(pixels(&memoire_tampon, TriCouleur::Rouge).is_empty());
+    }
+}
@@ -0,0 +1,83 @@
+/// Zone rectangulaire de l'écran exprimée en pixels
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Zone {
+    /// Abscisse du coin supérieur gauche
+    pub x: usize,
+    /// Ordonnée du coin supérieur gauche
+    pub y: usize,
+    /// Largeur
+    pub largeur: usize,
+    /// Hauteur
+    pub hauteur: usize,
+}
+
+impl Zone {
+    /// Zone commençant en (x, y)
+    pub fn new(x: usize, y: usize, largeur: usize, hauteur: usize) -> Self {
+        Self {
+            x,
+            y,
+            largeur,
+            hauteur,
+        }
+    }
+
+    /// Indiquer si la zone est vide
+    pub fn est_vide(&self) -> bool {
+        self.largeur == 0 || self.hauteur == 0
+    }
+
+    /// Indiquer si la zone est entièrement contenue dans un écran de dimensions données
+    pub fn est_contenue(&self, largeur: usize, hauteur: usize) -> bool {
+        self.x
+            .checked_add(self.largeur)
+            .is_some_and(|fin| fin <= largeur)
+            && self
+                .y
+                .checked_add(self.hauteur)
+                .is_some_and(|fin| fin <= hauteur)
+    }
+
+    /// Elargir horizontalement la zone aux multiples de 8 pixels.
+    /// Le contrôleur transmet les pixels par octets : l'abscisse de début et la largeur de la
+    /// fenêtre doivent être des multiples de 8.
+    pub fn aligner(&self) -> Zone {
+        let x = self.x - self.x % 8;
+        let x_fin = (self.x + self.largeur).div_ceil(8) * 8;
+        Zone {
+            x,
+            y: self.y,
+            largeur: x_fin - x,
+            hauteur: self.hauteur,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zone_contenue() {
+        assert!(Zone::new(0, 0, 800, 480).est_contenue(800, 480));
+        assert!(Zone::new(792, 479, 8, 1).est_contenue(800, 480));
+        assert!(!Zone::new(793, 0, 8, 1).est_contenue(800, 480));
+        assert!(!Zone::new(0, 470, 8, 11).est_contenue(800, 480));
+    }
+
+    #[test]
+    fn zone_contenue_sans_depassement() {
+        assert!(!Zone::new(usize::MAX, 0, 1, 1).est_contenue(800, 480));
+        assert!(!Zone::new(1, 0, usize::MAX, 1).est_contenue(800, 480));
+        assert!(!Zone::new(0, usize::MAX, 1, 1).est_contenue(800, 480));
+        assert!(!Zone::new(0, 2, 1, usize::MAX - 1).est_contenue(800, 480));
+        assert!(!Zone::new(usize::MAX, usize::MAX, usize::MAX, usize::MAX)
+            .est_contenue(usize::MAX, usize::MAX));
+    }
+
+    #[test]
+    fn alignement_sur_les_octets() {
+        assert_eq!(Zone::new(13, 7, 20, 5).aligner(), Zone::new(8, 7, 32, 5));
+        assert_eq!(Zone::new(16, 0, 8, 1).aligner(), Zone::new(16, 0, 8, 1));
+    }
+}
@@ -58,7 +58,7 @@ pub async fn verifier_localisation(
     let systeme_localisation_port_usb = systeme_localisation_port_usb.as_ref().unwrap();
 
     let mut systeme_localisation_allume =
-        match PeripheriqueUsb::changer_etat_usb(&systeme_localisation_port_usb, true) {
+        match PeripheriqueUsb::changer_etat_usb(systeme_localisation_port_usb, true) {
             true => true,
             false => {
                 log::warn!(
@@ -156,7 +156,7 @@ pub async fn verifier_localisation(
 
     if systeme_localisation_allume {
         systeme_localisation_allume =
-            match PeripheriqueUsb::changer_etat_usb(&systeme_localisation_port_usb, false) {
+            match PeripheriqueUsb::changer_etat_usb(systeme_localisation_port_usb, false) {
                 true => false,
                 false => {
                     log::warn!(